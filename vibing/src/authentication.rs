@@ -1,3 +1,6 @@
+pub mod jwt;
 pub mod keycloak_auth;
 pub mod request;
 pub mod response;
+pub mod roles;
+pub mod token_provider;
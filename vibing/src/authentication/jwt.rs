@@ -0,0 +1,70 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::authentication::response::KeycloakAccountRoles;
+
+/// The claims of a Keycloak access token used by vibing.
+///
+/// Tokens are decoded without verifying their signature: the claims are only used for display
+/// and local decisions, the backend remains the one validating them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct JwtClaims {
+    pub iss: Option<String>,
+    pub sub: Option<String>,
+    pub preferred_username: Option<String>,
+    pub exp: Option<i64>,
+    pub iat: Option<i64>,
+    pub scope: Option<String>,
+    /// Realm roles
+    pub realm_access: Option<KeycloakAccountRoles>,
+    /// Client roles, by client ID
+    pub resource_access: Option<BTreeMap<String, KeycloakAccountRoles>>,
+}
+
+impl JwtClaims {
+    pub fn scopes(&self) -> Vec<String> {
+        self.scope
+            .as_deref()
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_owned)
+            .collect()
+    }
+
+    /// Realm and client roles granted to the user, without duplicates
+    pub fn roles(&self) -> Vec<String> {
+        let mut roles: Vec<String> = self
+            .realm_access
+            .iter()
+            .chain(self.resource_access.iter().flat_map(BTreeMap::values))
+            .flat_map(|access| access.roles.iter().cloned())
+            .collect();
+        roles.sort();
+        roles.dedup();
+        roles
+    }
+}
+
+/// Decodes the payload of a JWT, returning `None` for opaque or malformed tokens
+pub fn decode_claims(token: &str) -> Option<JwtClaims> {
+    let payload = token.split('.').nth(1)?;
+    let decoded_payload = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    serde_json::from_slice::<JwtClaims>(&decoded_payload).ok()
+}
+
+/// The token with its signature dropped, keeping every claim readable, or `None` for opaque or
+/// malformed tokens
+pub fn strip_signature(token: &str) -> Option<String> {
+    let payload = token.split('.').nth(1)?;
+    let decoded_payload = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    let claims = serde_json::from_slice::<serde_json::Value>(&decoded_payload).ok()?;
+    Some(encode_unsigned(&claims))
+}
+
+/// Builds an unsigned JWT carrying the given claims, as issued by the mock server
+pub fn encode_unsigned(claims: &impl Serialize) -> String {
+    let header = URL_SAFE_NO_PAD.encode(br#"{"alg":"none","typ":"JWT"}"#);
+    let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims).unwrap_or_default());
+    format!("{header}.{payload}.")
+}
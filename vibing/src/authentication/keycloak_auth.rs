@@ -4,85 +4,126 @@ use reqwest::{
 };
 use std::time::Duration;
 use tokio::time;
+use tracing::info;
 
 use crate::{
     authentication::{
-        request::{self, KeycloakAuth, KeycloakDeviceCodeAuthCheck},
+        request::{
+            self, KeycloakAuth, KeycloakDeviceCodeAuthCheck, KeycloakLogout, KeycloakRefreshToken,
+        },
         response::{self, DeviceCodeAuth, KeycloakError, KeycloakSuccessfulAuthentication},
     },
-    creds, env,
+    browser, env,
     error::{AppError, Result, build_generic_error},
-    requests::{FetchOptions, fetch},
-    utils::verbose_print,
+    requests::{FetchOptions, execute, fetch, into_result},
+    utils::render_qr_code,
 };
 
+/// Scope requesting a refresh token which outlives the SSO session
+pub const OFFLINE_SCOPE: &str = "offline_access";
+
+/// How the device login is presented to the user
+#[derive(Debug, Clone, Copy)]
+pub struct LoginOptions {
+    /// Print the verification link as a QR code to scan from a phone
+    pub show_qr: bool,
+    /// Print the QR code light on dark, for dark terminal themes
+    pub invert_qr: bool,
+    /// Open the verification link when a browser is available
+    pub open_browser: bool,
+    /// Request an offline token, e.g. for unattended devices
+    pub offline: bool,
+}
+
 // TODO: Add clientId and clientSecret here
 pub struct KeycloakRequest {
     url: String,
 }
 
 impl KeycloakRequest {
-    pub fn new() -> KeycloakRequest {
-        let keycloak_host = env::get_env("KEYCLOAK_URL");
-        let keycloak_realm = env::get_env("KEYCLOAK_REALM");
-        return KeycloakRequest {
-            url: format!("{keycloak_host}/realms/{keycloak_realm}"),
-        };
+    /// Uses the `KEYCLOAK_URL` and `KEYCLOAK_REALM` environment variables
+    pub fn new() -> Result<KeycloakRequest> {
+        let keycloak_host = env::get_env("KEYCLOAK_URL")?;
+        let keycloak_realm = env::get_env("KEYCLOAK_REALM")?;
+        Ok(KeycloakRequest::from_url(
+            keycloak_host.as_str(),
+            keycloak_realm.as_str(),
+        ))
+    }
+
+    pub fn from_url(keycloak_host: &str, keycloak_realm: &str) -> KeycloakRequest {
+        KeycloakRequest {
+            url: format!(
+                "{}/realms/{keycloak_realm}",
+                keycloak_host.trim().trim_end_matches('/')
+            ),
+        }
     }
 
+    /// Logs in through the device authorization flow
     pub async fn authenticate(
         &self,
         client_id: &str,
         client_secret: &str,
-        verbose: bool,
-    ) -> Result<()> {
-        verbose_print(
-            verbose,
-            "Generating authentication _magic_ link... Please wait.",
-        );
-        let res = self.generate_device_code(client_id, client_secret).await?;
+        options: &LoginOptions,
+    ) -> Result<KeycloakSuccessfulAuthentication> {
+        info!("Generating authentication _magic_ link... Please wait.");
+        let scope = options.offline.then_some(OFFLINE_SCOPE);
+        let res = self
+            .generate_device_code(client_id, client_secret, scope)
+            .await?;
         println!("Device code generated!");
         println!(
             "Please, open your browser on any of your devices and go to this link to continue."
         );
         println!("\t- {}", res.verification_uri_complete);
         println!("\t- {} minutes remaining", res.expires_in / 60);
+        if options.show_qr
+            && let Some(qr_code) =
+                render_qr_code(res.verification_uri_complete.as_str(), options.invert_qr)
+        {
+            println!("Or scan this QR code with your phone:");
+            println!("{qr_code}");
+        }
+        println!(
+            "If the link does not work, go to {} and enter the code {}",
+            res.verification_uri, res.user_code
+        );
+        if options.open_browser && browser::is_available() {
+            match browser::open(res.verification_uri_complete.as_str()) {
+                Ok(()) => println!("The link has been opened in your browser."),
+                Err(err) => info!("Could not open the browser: {}", err.message()),
+            }
+        }
         loop {
             time::sleep(Duration::from_secs(5)).await;
             let is_authenticated = self
                 .check_authentication_status(res.device_code.as_str(), client_id, client_secret)
                 .await;
-            match is_authenticated {
-                Ok(authentication) => {
-                    verbose_print(verbose, "Authenticated!");
-                    verbose_print(verbose, "Storing credential");
-                    // TODO: move this call out of here (separation of responsibility)
-                    creds::store_cred("Vibing X Vibes - by Feis._.", &authentication.access_token)?;
-                    break;
-                }
-                Err(_) => (),
+            if let Ok(authentication) = is_authenticated {
+                info!("Authenticated!");
+                return Ok(authentication);
             }
         }
-        println!("Successfully authenticated! Now you can vibe ~");
-        Ok(())
     }
 
     pub async fn generate_device_code(
         &self,
         client_id: &str,
         client_secret: &str,
+        scope: Option<&str>,
     ) -> Result<DeviceCodeAuth> {
         let full_url = format!("{}/protocol/openid-connect/auth/device", self.url);
-        let keycloak_auth_req = KeycloakAuth::new(client_id, client_secret);
+        let mut keycloak_auth_req = KeycloakAuth::new(client_id, client_secret);
+        keycloak_auth_req.scope = scope.map(str::to_owned);
         let client = reqwest::Client::new();
-        let res = client
+        let req = client
             .post(full_url)
             .header("Content-Type", "application/x-www-form-urlencoded")
             .form(&keycloak_auth_req)
-            .send()
-            .await?;
-
-        let json_res = res.json::<DeviceCodeAuth>().await?;
+            .build()?;
+        let res = execute(&client, req).await?;
+        let json_res = res.json::<DeviceCodeAuth>()?;
         Ok(json_res)
     }
 
@@ -100,17 +141,41 @@ impl KeycloakRequest {
             client_secret,
         );
         let client = reqwest::Client::new();
-        let res = client
+        let req = client
             .post(full_url)
             .header("Content-Type", "application/x-www-form-urlencoded")
             .form(&req_body)
-            .send()
-            .await?;
-        if res.status().is_client_error() {
-            let json_res = res.json::<KeycloakError>().await?;
+            .build()?;
+        let res = execute(&client, req).await?;
+        if res.status.is_client_error() {
+            let json_res = res.json::<KeycloakError>()?;
+            return Err(AppError::KeycloakError(json_res));
+        }
+        let json_res = res.json::<KeycloakSuccessfulAuthentication>()?;
+        Ok(json_res)
+    }
+
+    /// Exchanges the refresh token for a new access token
+    pub async fn refresh_access_token(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+        refresh_token: &str,
+    ) -> Result<KeycloakSuccessfulAuthentication> {
+        let full_url = format!("{}/protocol/openid-connect/token", self.url);
+        let req_body = KeycloakRefreshToken::new(refresh_token, client_id, client_secret);
+        let client = reqwest::Client::new();
+        let req = client
+            .post(full_url)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .form(&req_body)
+            .build()?;
+        let res = execute(&client, req).await?;
+        if res.status.is_client_error() {
+            let json_res = res.json::<KeycloakError>()?;
             return Err(AppError::KeycloakError(json_res));
         }
-        let json_res = res.json::<KeycloakSuccessfulAuthentication>().await?;
+        let json_res = res.json::<KeycloakSuccessfulAuthentication>()?;
         Ok(json_res)
     }
 
@@ -123,30 +188,32 @@ impl KeycloakRequest {
         let full_url = format!("{}/protocol/openid-connect/token/introspect", self.url);
         let req_body = request::KeycloakJwtIntrospect::new(client_id, client_secret, token);
         let client = reqwest::Client::new();
-        let res = client
+        let req = client
             .post(full_url)
             .header("Content-Type", "application/x-www-form-urlencoded")
             .form(&req_body)
-            .send()
-            .await?;
-        let json_res = res.json::<response::KeycloakJwtActiveStatus>().await?;
+            .build()?;
+        let res = execute(&client, req).await?;
+        let json_res = res.json::<response::KeycloakJwtActiveStatus>()?;
         Ok(json_res.active)
     }
 
+    /// Revokes the token, `token_type_hint` being either `access_token` or `refresh_token`
     pub async fn revoke_token(
         &self,
         client_id: &str,
         client_secret: &str,
         token: &str,
-        verbose: bool,
+        token_type_hint: &str,
     ) -> Result<()> {
         let mut headers: HeaderMap<HeaderValue> = HeaderMap::new();
         headers.append(
             "Content-type",
             HeaderValue::from_str("application/x-www-form-urlencoded")
-                .map_err(|_| return AppError::Other(Box::new(build_generic_error(None))))?,
+                .map_err(|_| AppError::Other(Box::new(build_generic_error(None))))?,
         );
-        let req_body = request::KeycloakJwtIntrospect::new(client_id, client_secret, token);
+        let req_body =
+            request::KeycloakRevokeToken::new(client_id, client_secret, token, token_type_hint);
         let mut fetch_options = FetchOptions::new(
             self.url.clone(),
             "/protocol/openid-connect/revoke".to_owned(),
@@ -155,7 +222,73 @@ impl KeycloakRequest {
             None,
             Some(req_body),
         );
-        let _ = fetch::<request::KeycloakJwtIntrospect, ()>(&mut fetch_options, verbose).await?;
+        fetch::<request::KeycloakRevokeToken, ()>(&mut fetch_options).await?;
         Ok(())
     }
+
+    /// Ends the SSO session the refresh token belongs to, so the browser is logged out as well
+    pub async fn end_session(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+        refresh_token: &str,
+    ) -> Result<()> {
+        let full_url = format!("{}/protocol/openid-connect/logout", self.url);
+        let req_body = KeycloakLogout::new(client_id, client_secret, refresh_token);
+        let client = reqwest::Client::new();
+        let req = client
+            .post(full_url)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .form(&req_body)
+            .build()?;
+        let res = execute(&client, req).await?;
+        if res.status.is_client_error() {
+            let json_res = res.json::<KeycloakError>()?;
+            return Err(AppError::KeycloakError(json_res));
+        }
+        into_result::<()>(res)
+    }
+
+    /// Logs the session out of Keycloak: the SSO session the refresh token belongs to is ended,
+    /// then both tokens are revoked.
+    ///
+    /// Every step is attempted even when a previous one fails (e.g. the SSO session already
+    /// expired), the failed ones are returned together.
+    pub async fn logout(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+        access_token: &str,
+        refresh_token: Option<&str>,
+    ) -> Result<()> {
+        let mut failures = Vec::new();
+        if let Some(refresh_token) = refresh_token {
+            info!("Ending SSO session...");
+            if let Err(err) = self
+                .end_session(client_id, client_secret, refresh_token)
+                .await
+            {
+                failures.push(("ending the SSO session".to_owned(), err));
+            }
+            info!("Revoking refresh token...");
+            if let Err(err) = self
+                .revoke_token(client_id, client_secret, refresh_token, "refresh_token")
+                .await
+            {
+                failures.push(("revoking the refresh token".to_owned(), err));
+            }
+        }
+        info!("Revoking access token...");
+        if let Err(err) = self
+            .revoke_token(client_id, client_secret, access_token, "access_token")
+            .await
+        {
+            failures.push(("revoking the access token".to_owned(), err));
+        }
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::LogoutFailed(failures))
+        }
+    }
 }
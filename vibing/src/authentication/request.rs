@@ -1,9 +1,12 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct KeycloakAuth {
     pub client_id: String,
     pub client_secret: String,
+    /// Space separated scopes requested on top of the client's default ones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
 }
 
 impl KeycloakAuth {
@@ -11,6 +14,7 @@ impl KeycloakAuth {
         KeycloakAuth {
             client_id: client_id.to_owned(),
             client_secret: client_secret.to_owned(),
+            scope: None,
         }
     }
 }
@@ -39,7 +43,7 @@ impl KeycloakDeviceCodeAuthCheck {
     }
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct KeycloakJwtIntrospect {
     pub client_id: String,
     pub client_secret: String,
@@ -55,3 +59,64 @@ impl KeycloakJwtIntrospect {
         }
     }
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct KeycloakRefreshToken {
+    pub grant_type: String,
+    pub refresh_token: String,
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+impl KeycloakRefreshToken {
+    pub fn new(refresh_token: &str, client_id: &str, client_secret: &str) -> KeycloakRefreshToken {
+        KeycloakRefreshToken {
+            grant_type: "refresh_token".to_owned(),
+            refresh_token: refresh_token.to_owned(),
+            client_id: client_id.to_owned(),
+            client_secret: client_secret.to_owned(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct KeycloakRevokeToken {
+    pub client_id: String,
+    pub client_secret: String,
+    pub token: String,
+    /// Either `access_token` or `refresh_token`
+    pub token_type_hint: Option<String>,
+}
+
+impl KeycloakRevokeToken {
+    pub fn new(
+        client_id: &str,
+        client_secret: &str,
+        token: &str,
+        token_type_hint: &str,
+    ) -> KeycloakRevokeToken {
+        KeycloakRevokeToken {
+            client_id: client_id.to_owned(),
+            client_secret: client_secret.to_owned(),
+            token: token.to_owned(),
+            token_type_hint: Some(token_type_hint.to_owned()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct KeycloakLogout {
+    pub client_id: String,
+    pub client_secret: String,
+    pub refresh_token: String,
+}
+
+impl KeycloakLogout {
+    pub fn new(client_id: &str, client_secret: &str, refresh_token: &str) -> KeycloakLogout {
+        KeycloakLogout {
+            client_id: client_id.to_owned(),
+            client_secret: client_secret.to_owned(),
+            refresh_token: refresh_token.to_owned(),
+        }
+    }
+}
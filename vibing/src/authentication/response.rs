@@ -1,21 +1,23 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct KeycloakError {
     pub error: String,
     pub error_description: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct KeycloakSuccessfulAuthentication {
     pub access_token: String,
     pub expires_in: i32,
+    pub refresh_token: Option<String>,
+    pub refresh_expires_in: Option<i32>,
     pub token_type: String,
     pub session_state: String,
     pub scope: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DeviceCodeAuth {
     pub device_code: String,
     pub user_code: String,
@@ -25,8 +27,9 @@ pub struct DeviceCodeAuth {
     pub interval: i32,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct KeycloakAccountRoles {
+    #[serde(default)]
     pub roles: Vec<String>,
 }
 
@@ -57,7 +60,7 @@ pub struct KeycloakJwtIntrospect {
     pub active: bool,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct KeycloakJwtActiveStatus {
     pub active: bool,
 }
@@ -0,0 +1,29 @@
+use crate::{
+    authentication::{jwt::decode_claims, token_provider::default_provider},
+    error::{AppError, Result},
+};
+
+/// Realm or client role allowing to manage every club
+pub const CLUB_ADMIN_ROLE: &str = "club-admin";
+
+/// Roles of the current user, read from the access token without contacting the server.
+///
+/// `None` when there is no token or its roles cannot be read (e.g. opaque tokens), leaving the
+/// decision to the backend.
+pub fn current_roles() -> Option<Vec<String>> {
+    let claims = decode_claims(default_provider().current_token()?.as_str())?;
+    if claims.realm_access.is_none() && claims.resource_access.is_none() {
+        return None;
+    }
+    Some(claims.roles())
+}
+
+/// Fails fast when the token shows that the user lacks the role
+pub fn require_role(role: &str) -> Result<()> {
+    match current_roles() {
+        Some(roles) if !roles.iter().any(|granted_role| granted_role == role) => {
+            Err(AppError::MissingRole(role.to_owned()))
+        }
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,361 @@
+use async_trait::async_trait;
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::{
+    authentication::{jwt::decode_claims, keycloak_auth::KeycloakRequest},
+    config::Config,
+    creds::{
+        self, accounts,
+        record::{CredentialRecord, describe_timestamp, now},
+    },
+    env,
+    error::{AppError, Result},
+};
+
+/// Environment variable holding an access token, used by [`EnvTokenProvider`]
+pub const TOKEN_ENV: &str = "VIBING_TOKEN";
+/// Environment variable holding the path of a file containing an access token
+pub const TOKEN_FILE_ENV: &str = "VIBING_TOKEN_FILE";
+
+/// Refresh the access token this long before it actually expires
+const EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+const DEFAULT_OFFLINE_EXPIRY_WARNING_DAYS: u32 = 7;
+
+/// A source of access tokens used to authorize backend requests
+#[async_trait]
+pub trait TokenProvider: Send + Sync {
+    async fn token(&self) -> Result<String>;
+
+    /// The token the provider holds right now, without refreshing nor reaching the network,
+    /// `None` when there is none
+    fn current_token(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Reads the token from the device's secure storage, refreshing it through Keycloak when it is
+/// expired and a refresh token was stored at login
+#[derive(Default)]
+pub struct KeyringTokenProvider {
+    /// Service and user of the entry, the active account of the current profile when unset
+    entry: Option<(String, String)>,
+}
+
+impl KeyringTokenProvider {
+    pub fn new(service: &str, user: &str) -> KeyringTokenProvider {
+        KeyringTokenProvider {
+            entry: Some((service.to_owned(), user.to_owned())),
+        }
+    }
+}
+
+impl KeyringTokenProvider {
+    fn entry(&self) -> Result<(String, String)> {
+        match &self.entry {
+            Some(entry) => Ok(entry.clone()),
+            None => accounts::active_entry(),
+        }
+    }
+}
+
+#[async_trait]
+impl TokenProvider for KeyringTokenProvider {
+    async fn token(&self) -> Result<String> {
+        let (service, user) = self.entry()?;
+        let mut record = creds::get_record(service.as_str(), user.as_str())?;
+        if let Some(refresh_token) = &record.refresh_token
+            && record.is_access_expired(EXPIRY_MARGIN.as_secs() as i64)
+            && !record.is_refresh_expired()
+        {
+            let keycloak = KeycloakRequest::new()?;
+            let client_id = env::get_env("VIBING_DEVICE_CODE_CLIENT_ID")?;
+            let client_secret = env::get_env("VIBING_CLIENT_SECRET")?;
+            let authentication = keycloak
+                .refresh_access_token(
+                    client_id.as_str(),
+                    client_secret.as_str(),
+                    refresh_token.as_str(),
+                )
+                .await?;
+            record = record.refreshed(&authentication);
+            creds::open_store()?.set(
+                service.as_str(),
+                user.as_str(),
+                record.to_secret()?.as_str(),
+            )?;
+        }
+        warn_offline_expiry(&record);
+        Ok(record.access_token)
+    }
+
+    fn current_token(&self) -> Option<String> {
+        let (service, user) = self.entry().ok()?;
+        creds::get_record(service.as_str(), user.as_str())
+            .ok()
+            .map(|record| record.access_token)
+    }
+}
+
+/// Warns in the logs, so that it does not mix with the command's output, when the offline
+/// session of an unattended device is about to expire or has expired.
+///
+/// Only a courtesy: an unreadable config falls back to the default delay rather than failing
+/// the request.
+fn warn_offline_expiry(record: &CredentialRecord) {
+    let Some(expires_at) = record.refresh_expires_at else {
+        return;
+    };
+    if !record.is_offline() {
+        return;
+    }
+    let remaining = expires_at - now();
+    if remaining <= 0 {
+        warn!(
+            "The offline session expired {}, run 'vibing auth login --offline' to log in again.",
+            describe_timestamp(expires_at)
+        );
+        return;
+    }
+    let warning_days = Config::load()
+        .ok()
+        .and_then(|config| config.offline_expiry_warning_days)
+        .unwrap_or(DEFAULT_OFFLINE_EXPIRY_WARNING_DAYS);
+    if remaining <= i64::from(warning_days) * 86400 {
+        warn!(
+            "The offline session expires {}, run 'vibing auth login --offline' to renew it.",
+            describe_timestamp(expires_at)
+        );
+    }
+}
+
+/// Reads the token from an environment variable, `VIBING_TOKEN` by default
+pub struct EnvTokenProvider {
+    variable: String,
+}
+
+impl EnvTokenProvider {
+    pub fn new(variable: &str) -> EnvTokenProvider {
+        EnvTokenProvider {
+            variable: variable.to_owned(),
+        }
+    }
+}
+
+impl Default for EnvTokenProvider {
+    fn default() -> Self {
+        EnvTokenProvider::new(TOKEN_ENV)
+    }
+}
+
+#[async_trait]
+impl TokenProvider for EnvTokenProvider {
+    async fn token(&self) -> Result<String> {
+        self.current_token().ok_or_else(|| {
+            AppError::TokenUnavailable(format!(
+                "The `{}` environment variable is not set",
+                self.variable
+            ))
+        })
+    }
+
+    fn current_token(&self) -> Option<String> {
+        std::env::var(&self.variable)
+            .ok()
+            .map(|token| token.trim().to_owned())
+            .filter(|token| !token.is_empty())
+    }
+}
+
+/// Reads the token from a file, re-reading it on every request so it can be rotated externally
+pub struct FileTokenProvider {
+    path: PathBuf,
+}
+
+impl FileTokenProvider {
+    pub fn new(path: impl Into<PathBuf>) -> FileTokenProvider {
+        FileTokenProvider { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl TokenProvider for FileTokenProvider {
+    async fn token(&self) -> Result<String> {
+        let content = tokio::fs::read_to_string(&self.path).await.map_err(|err| {
+            AppError::TokenUnavailable(format!(
+                "Could not read the token file `{}`: {err}",
+                self.path.display()
+            ))
+        })?;
+        let token = content.trim();
+        if token.is_empty() {
+            return Err(AppError::TokenUnavailable(format!(
+                "The token file `{}` is empty",
+                self.path.display()
+            )));
+        }
+        Ok(token.to_owned())
+    }
+
+    fn current_token(&self) -> Option<String> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .map(|content| content.trim().to_owned())
+            .filter(|token| !token.is_empty())
+    }
+}
+
+/// Always returns the same token
+pub struct StaticTokenProvider {
+    token: String,
+}
+
+impl StaticTokenProvider {
+    pub fn new(token: impl Into<String>) -> StaticTokenProvider {
+        StaticTokenProvider {
+            token: token.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl TokenProvider for StaticTokenProvider {
+    async fn token(&self) -> Result<String> {
+        Ok(self.token.clone())
+    }
+
+    fn current_token(&self) -> Option<String> {
+        Some(self.token.clone())
+    }
+}
+
+struct RefreshState {
+    access_token: Option<String>,
+    expires_at: Option<Instant>,
+    refresh_token: String,
+}
+
+/// Exchanges a refresh token for access tokens, refreshing them shortly before they expire
+pub struct KeycloakTokenProvider {
+    keycloak: KeycloakRequest,
+    client_id: String,
+    client_secret: String,
+    state: Mutex<RefreshState>,
+}
+
+impl KeycloakTokenProvider {
+    pub fn new(
+        keycloak: KeycloakRequest,
+        client_id: &str,
+        client_secret: &str,
+        refresh_token: &str,
+    ) -> KeycloakTokenProvider {
+        KeycloakTokenProvider {
+            keycloak,
+            client_id: client_id.to_owned(),
+            client_secret: client_secret.to_owned(),
+            state: Mutex::new(RefreshState {
+                access_token: None,
+                expires_at: None,
+                refresh_token: refresh_token.to_owned(),
+            }),
+        }
+    }
+
+    /// Returns the latest refresh token, which Keycloak may rotate on every refresh
+    pub async fn refresh_token(&self) -> String {
+        self.state.lock().await.refresh_token.clone()
+    }
+}
+
+#[async_trait]
+impl TokenProvider for KeycloakTokenProvider {
+    async fn token(&self) -> Result<String> {
+        let mut state = self.state.lock().await;
+        if let (Some(access_token), Some(expires_at)) = (&state.access_token, state.expires_at)
+            && Instant::now() + EXPIRY_MARGIN < expires_at
+        {
+            return Ok(access_token.clone());
+        }
+        let authentication = self
+            .keycloak
+            .refresh_access_token(
+                self.client_id.as_str(),
+                self.client_secret.as_str(),
+                state.refresh_token.as_str(),
+            )
+            .await?;
+        state.expires_at =
+            Some(Instant::now() + Duration::from_secs(authentication.expires_in.max(0) as u64));
+        state.access_token = Some(authentication.access_token.clone());
+        if let Some(refresh_token) = authentication.refresh_token {
+            state.refresh_token = refresh_token;
+        }
+        Ok(authentication.access_token)
+    }
+
+    fn current_token(&self) -> Option<String> {
+        self.state.try_lock().ok()?.access_token.clone()
+    }
+}
+
+/// Keeps the token of another provider until shortly before it expires, so that a long-lived
+/// session (e.g. the shell) does not read the secure storage for every request
+pub struct CachedTokenProvider {
+    inner: Arc<dyn TokenProvider>,
+    cached_token: Mutex<Option<String>>,
+}
+
+impl CachedTokenProvider {
+    pub fn new(inner: Arc<dyn TokenProvider>) -> CachedTokenProvider {
+        CachedTokenProvider {
+            inner,
+            cached_token: Mutex::new(None),
+        }
+    }
+}
+
+#[async_trait]
+impl TokenProvider for CachedTokenProvider {
+    async fn token(&self) -> Result<String> {
+        let mut cached_token = self.cached_token.lock().await;
+        if let Some(token) = cached_token.as_ref() {
+            // Tokens whose expiry cannot be read are kept for the whole session
+            let expires_at = decode_claims(token).and_then(|claims| claims.exp);
+            if expires_at
+                .is_none_or(|expires_at| now() + (EXPIRY_MARGIN.as_secs() as i64) < expires_at)
+            {
+                return Ok(token.clone());
+            }
+        }
+        let token = self.inner.token().await?;
+        *cached_token = Some(token.clone());
+        Ok(token)
+    }
+
+    fn current_token(&self) -> Option<String> {
+        let cached_token = self.cached_token.try_lock().ok()?.clone();
+        cached_token.or_else(|| self.inner.current_token())
+    }
+}
+
+/// Picks the token source from the environment: `VIBING_TOKEN`, then `VIBING_TOKEN_FILE`,
+/// falling back to the device's secure storage
+pub fn default_provider() -> Arc<dyn TokenProvider> {
+    let is_set =
+        |variable: &str| std::env::var(variable).is_ok_and(|value| !value.trim().is_empty());
+    if is_set(TOKEN_ENV) {
+        return Arc::new(EnvTokenProvider::default());
+    }
+    if is_set(TOKEN_FILE_ENV) {
+        return Arc::new(FileTokenProvider::new(
+            std::env::var(TOKEN_FILE_ENV).unwrap_or_default(),
+        ));
+    }
+    Arc::new(KeyringTokenProvider::default())
+}
@@ -0,0 +1,47 @@
+use clap::Parser;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use vibing::{
+    authentication::roles::CLUB_ADMIN_ROLE,
+    error::handle,
+    mock::{self, MockState},
+};
+
+/// Mock Keycloak and backend server for local testing and demos
+#[derive(Parser, Debug)]
+#[clap(author, version)]
+struct MockArgs {
+    /// Port to listen on
+    #[arg(short, long, default_value_t = 8080)]
+    port: u16,
+
+    /// Address to bind
+    #[arg(long, default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST))]
+    host: IpAddr,
+
+    /// Start with an empty club list instead of the sample clubs
+    #[arg(long)]
+    empty: bool,
+
+    /// Comma separated realm roles granted to the mock user
+    #[arg(long, value_delimiter = ',', default_value = CLUB_ADMIN_ROLE)]
+    roles: Vec<String>,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = MockArgs::parse();
+    let addr = SocketAddr::new(args.host, args.port);
+    let state = if args.empty {
+        MockState::new()
+    } else {
+        MockState::seeded()
+    };
+    state.lock().roles = args.roles;
+    println!("Vibing mock listening on http://{addr}");
+    println!("Point vibing to it with:");
+    println!("\tKEYCLOAK_URL=http://{addr}");
+    println!("\tBACKEND_URL=http://{addr}");
+    if let Err(err) = mock::serve(addr, state).await {
+        handle(err);
+    }
+}
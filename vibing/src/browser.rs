@@ -0,0 +1,38 @@
+use std::process::{Command, Stdio};
+
+use crate::error::Result;
+
+/// Whether a graphical browser can be expected to show up on the user's screen.
+///
+/// SSH sessions and Linux sessions without a display server are considered headless.
+pub fn is_available() -> bool {
+    let is_set = |variable: &str| std::env::var_os(variable).is_some_and(|value| !value.is_empty());
+    if is_set("SSH_CONNECTION") || is_set("SSH_CLIENT") || is_set("SSH_TTY") {
+        return false;
+    }
+    if cfg!(any(target_os = "macos", target_os = "windows")) {
+        return true;
+    }
+    is_set("DISPLAY") || is_set("WAYLAND_DISPLAY")
+}
+
+/// Opens the URL with the platform's default opener, without waiting for the browser to exit
+pub fn open(url: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        // The empty argument is the window title `start` expects before the target
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
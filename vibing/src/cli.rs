@@ -1,2 +1,5 @@
+pub mod alias;
 pub mod args;
 pub mod matcher;
+pub mod session;
+pub mod shell;
@@ -0,0 +1,228 @@
+use clap::CommandFactory;
+use std::collections::{BTreeMap, HashSet};
+use tracing::warn;
+
+use crate::{
+    cli::args::VibingCliParser,
+    config::Config,
+    error::{AppError, Result},
+};
+
+/// Expands the alias the command line starts with (after the global options) into its command,
+/// e.g. `vibing open --size 10` into `vibing club list --total --size 10`.
+///
+/// `args` leaves out the program name. Built-in commands are never expanded, so aliases cannot
+/// shadow them. An unreadable configuration only prints a warning, so that `--help` and the
+/// commands fixing it keep working.
+pub fn expand(args: Vec<String>) -> Result<Vec<String>> {
+    match Config::load() {
+        Ok(config) => expand_with(args, &config.aliases),
+        Err(err) => {
+            let message = err.message();
+            warn!(
+                "Aliases are not expanded, the configuration cannot be read ({}).",
+                message.lines().next().unwrap_or_default()
+            );
+            Ok(args)
+        }
+    }
+}
+
+/// Aliases may expand to other aliases, as long as they do not loop back. An alias starting with
+/// its own name, e.g. `list` for `list --total`, is expanded only once, as in shells.
+pub fn expand_with(
+    mut args: Vec<String>,
+    aliases: &BTreeMap<String, String>,
+) -> Result<Vec<String>> {
+    if aliases.is_empty() {
+        return Ok(args);
+    }
+    let builtin_commands = builtin_commands();
+    let mut expanded_aliases: Vec<String> = Vec::new();
+    loop {
+        let Some(index) = command_index(&args) else {
+            return Ok(args);
+        };
+        let name = args[index].clone();
+        if builtin_commands.contains(&name) {
+            return Ok(args);
+        }
+        let Some(command) = aliases.get(&name) else {
+            return Ok(args);
+        };
+        let is_recursive = expanded_aliases.contains(&name);
+        expanded_aliases.push(name.clone());
+        if is_recursive {
+            return Err(AppError::AliasError(format!(
+                "The `{}` alias expands recursively: {}",
+                expanded_aliases[0],
+                expanded_aliases.join(" -> ")
+            )));
+        }
+        let words = split_command(name.as_str(), command.as_str())?;
+        let is_self_reference = words[0] == name;
+        args.splice(index..=index, words);
+        if is_self_reference {
+            return Ok(args);
+        }
+    }
+}
+
+/// Defines or replaces the alias
+pub fn set(name: &str, command: &str) -> Result<()> {
+    if name.is_empty() || name.starts_with('-') || name.contains(char::is_whitespace) {
+        return Err(AppError::AliasError(format!(
+            "`{name}` is not a valid alias name, use a single word not starting with `-`."
+        )));
+    }
+    if builtin_commands().contains(name) {
+        return Err(AppError::AliasError(format!(
+            "`{name}` is a built-in command, which an alias cannot replace."
+        )));
+    }
+    split_command(name, command)?;
+    let mut config = Config::load()?;
+    config.aliases.insert(name.to_owned(), command.to_owned());
+    expand_with(vec![name.to_owned()], &config.aliases)?;
+    config.save()
+}
+
+pub fn remove(name: &str) -> Result<()> {
+    let mut config = Config::load()?;
+    if config.aliases.remove(name).is_none() {
+        return Err(AppError::AliasError(format!(
+            "There is no `{name}` alias, run `vibing alias list` to list them."
+        )));
+    }
+    config.save()
+}
+
+fn split_command(name: &str, command: &str) -> Result<Vec<String>> {
+    match shlex::split(command) {
+        Some(words) if !words.is_empty() => Ok(words),
+        Some(_) => Err(AppError::AliasError(format!(
+            "The `{name}` alias has an empty command."
+        ))),
+        None => Err(AppError::AliasError(format!(
+            "The command of the `{name}` alias has unbalanced quotes."
+        ))),
+    }
+}
+
+/// Names and aliases of the subcommands, including `help`
+fn builtin_commands() -> HashSet<String> {
+    let command = VibingCliParser::command();
+    let mut builtin_commands: HashSet<String> = command
+        .get_subcommands()
+        .flat_map(|subcommand| {
+            std::iter::once(subcommand.get_name()).chain(subcommand.get_all_aliases())
+        })
+        .map(str::to_owned)
+        .collect();
+    builtin_commands.insert("help".to_owned());
+    builtin_commands
+}
+
+/// Position of the command, skipping the global options and their values
+fn command_index(args: &[String]) -> Option<usize> {
+    let command = VibingCliParser::command();
+    let options_with_value: HashSet<String> = command
+        .get_arguments()
+        .filter(|arg| arg.get_action().takes_values())
+        .flat_map(|arg| {
+            let long = arg.get_long().map(|long| format!("--{long}"));
+            let short = arg.get_short().map(|short| format!("-{short}"));
+            long.into_iter().chain(short)
+        })
+        .collect();
+    let mut index = 0;
+    while let Some(arg) = args.get(index) {
+        if arg == "--" {
+            return None;
+        }
+        if !arg.starts_with('-') {
+            return Some(index);
+        }
+        index += if options_with_value.contains(arg) {
+            2
+        } else {
+            1
+        };
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aliases(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(name, command)| (name.to_string(), command.to_string()))
+            .collect()
+    }
+
+    fn args(line: &str) -> Vec<String> {
+        shlex::split(line).unwrap()
+    }
+
+    #[test]
+    fn expands_the_command_after_the_global_options() {
+        let aliases = aliases(&[("open", "club list --total --size 3")]);
+        assert_eq!(
+            expand_with(args("--log-level debug open --size 10"), &aliases).unwrap(),
+            args("--log-level debug club list --total --size 3 --size 10")
+        );
+    }
+
+    #[test]
+    fn expands_aliases_of_aliases() {
+        let aliases = aliases(&[
+            ("open", "club list --total"),
+            ("disco", "open --query '$.content[*]'"),
+        ]);
+        assert_eq!(
+            expand_with(args("disco"), &aliases).unwrap(),
+            args("club list --total --query '$.content[*]'")
+        );
+    }
+
+    #[test]
+    fn leaves_builtin_commands_and_unknown_words() {
+        let aliases = aliases(&[("club", "tui"), ("open", "club list")]);
+        for line in ["club get 1", "other", "-- open", "--help"] {
+            assert_eq!(expand_with(args(line), &aliases).unwrap(), args(line));
+        }
+    }
+
+    #[test]
+    fn expands_self_references_once() {
+        let aliases = aliases(&[("list", "list --total")]);
+        assert_eq!(
+            expand_with(args("list"), &aliases).unwrap(),
+            args("list --total")
+        );
+    }
+
+    #[test]
+    fn rejects_recursive_aliases() {
+        let aliases = aliases(&[("first", "second --total"), ("second", "first")]);
+        let Err(AppError::AliasError(reason)) = expand_with(args("first"), &aliases) else {
+            panic!("the recursion is not detected");
+        };
+        assert_eq!(
+            reason,
+            "The `first` alias expands recursively: first -> second -> first"
+        );
+    }
+
+    #[test]
+    fn rejects_unbalanced_quotes() {
+        let aliases = aliases(&[("open", "club list --query '$")]);
+        assert!(matches!(
+            expand_with(args("open"), &aliases),
+            Err(AppError::AliasError(_))
+        ));
+    }
+}
@@ -1,4 +1,20 @@
-use clap::{Args, Parser, Subcommand};
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use clap_complete::{ArgValueCandidates, Shell};
+use std::{path::PathBuf, time::Duration};
+
+use crate::{
+    authentication::roles::CLUB_ADMIN_ROLE,
+    error::Result,
+    features::club::cache::{club_candidates, club_id_candidates},
+    logging::{LogFormat, LogLevel},
+    output::OutputFormat,
+    requests::PageDirection,
+    watch::{WatchOptions, condition::Condition},
+};
+
+/// Roles required to run commands, by subcommand path. Checked locally against the token
+/// before running the command and shown in the help.
+pub const COMMAND_ROLES: &[(&[&str], &str)] = &[(&["club", "delete"], CLUB_ADMIN_ROLE)];
 
 #[derive(Parser, Debug)]
 #[clap(author, version)]
@@ -6,9 +22,70 @@ pub struct VibingCliParser {
     #[command(subcommand)]
     pub command: BaseCommands,
 
-    /// Print out the trace from all the methods called
-    #[arg(short, long)]
-    pub verbose: bool,
+    /// Log more to stderr: `-v` for the steps taken, `-vv` for the requests and `-vvv` for
+    /// everything
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Level of the logs, taking precedence over `-v` (defaults to the configured one or `warn`)
+    #[arg(long, global = true, value_enum)]
+    pub log_level: Option<LogLevel>,
+
+    /// Format of the logs, `json` being suited to log aggregation
+    #[arg(long, global = true, value_enum)]
+    pub log_format: Option<LogFormat>,
+
+    /// Also write the logs to daily rotated files in the state directory, e.g.
+    /// `~/.local/state/vibing/logs`
+    #[arg(long, global = true)]
+    pub log_file: bool,
+
+    /// Log method, URL, headers, bodies, status and timing of every HTTP request (secrets are redacted)
+    #[arg(long, global = true)]
+    pub trace_http: bool,
+
+    /// Log an equivalent `curl` command for every HTTP request
+    #[arg(long, global = true)]
+    pub print_curl: bool,
+
+    /// Save every request/response pair as a cassette in the given directory
+    #[arg(long, global = true, value_name = "DIR", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Serve responses from the cassette in the given directory instead of the network
+    #[arg(long, global = true, value_name = "DIR")]
+    pub replay: Option<PathBuf>,
+
+    /// Never wait for the user: fail instead of prompting, including to unlock the keyring, e.g.
+    /// on unattended kiosks (also enabled by `VIBING_NON_INTERACTIVE`)
+    #[arg(long, global = true)]
+    pub non_interactive: bool,
+
+    /// Profile whose accounts are used, defaults to `VIBING_PROFILE` or the configured default
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
+    #[arg(short, long, global = true, value_enum, default_value_t)]
+    pub output: OutputFormat,
+
+    /// Only print these fields of the response, e.g. `club_name,address`
+    #[arg(long, global = true, value_delimiter = ',')]
+    pub fields: Vec<String>,
+
+    /// JSONPath expression selecting what to print from the response, e.g.
+    /// `$.content[?@.category == 'Disco'].clubName`
+    #[arg(long, global = true)]
+    pub query: Option<String>,
+
+    /// Handlebars template to render the response through, e.g.
+    /// `{{club_name}} ({{category}}) {{date opening_time "%H:%M"}}-{{date closing_time "%H:%M"}}`.
+    /// Pages are rendered as a whole, select their clubs with `--query '$.content[*]'`
+    #[arg(long, global = true, conflicts_with = "template_file")]
+    pub template: Option<String>,
+
+    /// Read the output template from the file
+    #[arg(long, global = true, value_name = "FILE")]
+    pub template_file: Option<PathBuf>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -18,24 +95,277 @@ pub enum BaseCommands {
     Auth(AuthArgs),
     #[command(subcommand)]
     Club(ClubArgs),
+    /// Credential helper speaking the `git credential` protocol on stdin/stdout, so other tools
+    /// can reuse vibing's login
+    #[command(subcommand)]
+    Credential(CredentialArgs),
+    /// Print the shell completion script
+    Completions(CompletionsArgs),
+    /// Generate the manual pages
+    Man(ManArgs),
+    /// Run commands interactively, keeping the session between them
+    Shell,
+    /// Browse and manage the clubs in a full-screen terminal UI
+    Tui,
+    /// Define shortcuts for commands, e.g. `vibing alias set open club list --total`
+    #[command(subcommand)]
+    Alias(AliasArgs),
+    /// Manage the plugins, executables named `vibing-<command>` on `PATH` run as `vibing <command>`
+    #[command(subcommand)]
+    Plugins(PluginsArgs),
+    #[command(external_subcommand)]
+    External(Vec<String>),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AliasArgs {
+    /// Define or replace an alias
+    Set(AliasSetArgs),
+    /// List the aliases
+    List,
+    /// Remove an alias
+    Remove(AliasRemoveArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct AliasSetArgs {
+    pub name: String,
+
+    /// Command the alias runs, as one quoted string or as separate words. Arguments given to the
+    /// alias are appended to it
+    #[arg(required = true, num_args = 1.., trailing_var_arg = true, allow_hyphen_values = true)]
+    pub command: Vec<String>,
+}
+
+impl AliasSetArgs {
+    /// The command as written in the configuration file
+    pub fn command_line(&self) -> String {
+        match self.command.as_slice() {
+            [command] => command.clone(),
+            words => shlex::try_join(words.iter().map(String::as_str))
+                .unwrap_or_else(|_| words.join(" ")),
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct AliasRemoveArgs {
+    pub name: String,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PluginsArgs {
+    /// List the installed plugins
+    List,
+}
+
+#[derive(Args, Debug)]
+pub struct CompletionsArgs {
+    /// e.g. add `source <(vibing completions bash)` to `~/.bashrc`
+    #[arg(value_enum)]
+    pub shell: Shell,
+
+    /// Generate a self-contained script, without completing values such as club IDs
+    #[arg(long = "static")]
+    pub static_script: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ManArgs {
+    /// Write a page for every command into the directory instead of printing the main page
+    #[arg(long, value_name = "DIR")]
+    pub out_dir: Option<PathBuf>,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum AuthArgs {
     /// Initiate the login process
-    Login,
+    Login(LoginArgs),
     /// Logout from the current logger in account
-    Logout,
+    Logout(LogoutArgs),
     /// Check your current authentication status
     Check,
+    /// Show which credential storage backend is in use
+    Storage,
+    /// Print a valid access token, to be used by other tools
+    Token,
+    /// Show the account and expiry of the stored login, without contacting the server
+    Info,
+    /// List the accounts logged in within the profile
+    Accounts,
+    /// Use another logged in account for the next requests
+    Switch(SwitchArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct LoginArgs {
+    /// Name to save the account under, defaults to the username of the token
+    #[arg(long)]
+    pub account: Option<String>,
+
+    /// Do not print the login link as a QR code
+    #[arg(long)]
+    pub no_qr: bool,
+
+    /// Print the QR code light on dark (`true`, for dark terminal themes) or dark on light
+    /// (`false`), defaults to the `qr_invert` setting
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    pub qr_invert: Option<bool>,
+
+    /// Do not open the login link in the browser
+    #[arg(long)]
+    pub no_browser: bool,
+
+    /// Request an offline token, which keeps unattended devices logged in for weeks
+    #[arg(long)]
+    pub offline: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct LogoutArgs {
+    /// Logout from every account of every profile
+    #[arg(long)]
+    pub all_profiles: bool,
+
+    /// Only remove the stored credentials, without ending the session on the server
+    #[arg(long)]
+    pub local_only: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct SwitchArgs {
+    /// Account to switch to, as listed by `vibing auth accounts`
+    pub user: String,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CredentialArgs {
+    /// Print the stored credential
+    Get,
+    /// Store the credential read from stdin
+    Store,
+    /// Remove the stored credential
+    Erase,
+}
+
+impl CredentialArgs {
+    pub fn operation(&self) -> &'static str {
+        match self {
+            CredentialArgs::Get => "get",
+            CredentialArgs::Store => "store",
+            CredentialArgs::Erase => "erase",
+        }
+    }
 }
 
 #[derive(Subcommand, Debug)]
 pub enum ClubArgs {
+    /// Show the details of a club
     Get(ClubGetArgs),
+    /// List the clubs page by page
+    List(ClubListArgs),
+    /// Delete a club
+    Delete(ClubDeleteArgs),
 }
 
 #[derive(Args, Debug)]
 pub struct ClubGetArgs {
+    /// ID or name of the club, names being matched loosely (e.g. `amnesia`, `bhain`)
+    #[arg(value_name = "CLUB", add = ArgValueCandidates::new(club_candidates))]
+    pub club: String,
+
+    /// Only accept a club with exactly this name, failing instead of prompting when several
+    /// clubs share it
+    #[arg(long)]
+    pub exact: bool,
+
+    #[command(flatten)]
+    pub watch: WatchArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct ClubDeleteArgs {
+    #[arg(add = ArgValueCandidates::new(club_id_candidates))]
     pub club_id: u32,
 }
+
+#[derive(Args, Debug)]
+pub struct ClubListArgs {
+    /// Page to show, starting from 0
+    #[arg(short, long)]
+    pub page: Option<u32>,
+
+    /// Number of clubs per page
+    #[arg(short, long)]
+    pub size: Option<u32>,
+
+    /// Field to sort the clubs by (e.g. `clubName`)
+    #[arg(long)]
+    pub sort: Option<String>,
+
+    #[arg(long, value_enum)]
+    pub direction: Option<SortDirection>,
+
+    /// Include the total number of clubs in the response
+    #[arg(long)]
+    pub total: bool,
+
+    #[command(flatten)]
+    pub watch: WatchArgs,
+}
+
+/// Order of `club list`, kept apart from the client's [`PageDirection`] so that the library does
+/// not depend on the command line
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum SortDirection {
+    Desc,
+    Asc,
+}
+
+impl From<SortDirection> for PageDirection {
+    fn from(direction: SortDirection) -> PageDirection {
+        match direction {
+            SortDirection::Desc => PageDirection::Desc,
+            SortDirection::Asc => PageDirection::Asc,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct WatchArgs {
+    /// Fetch again every SECONDS (2 by default), redrawing the response and highlighting what
+    /// changed, e.g. `--watch` or `--watch 5`
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        num_args = 0..=1,
+        default_missing_value = "2",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub watch: Option<u64>,
+
+    /// Stop watching once the condition holds, e.g. `deleted == true` or `totalCount >= 10`, and
+    /// exit with 0 (stopping with Ctrl-C exits with 130)
+    #[arg(long, value_name = "CONDITION", requires = "watch")]
+    pub until: Option<String>,
+}
+
+impl WatchArgs {
+    /// `None` unless `--watch` is given
+    pub fn options(&self, title: String) -> Result<Option<WatchOptions>> {
+        let Some(interval) = self.watch else {
+            return Ok(None);
+        };
+        Ok(Some(WatchOptions {
+            interval: Duration::from_secs(interval),
+            until: self.until.as_deref().map(Condition::parse).transpose()?,
+            title,
+        }))
+    }
+}
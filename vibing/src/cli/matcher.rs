@@ -1,90 +1,697 @@
 use crate::{
-    authentication::keycloak_auth::KeycloakRequest,
-    cli::args::{AuthArgs, BaseCommands, ClubArgs, VibingCliParser},
-    creds, env,
-    error::Result,
-    features::club::club_requests::get_club_info,
-    utils::verbose_print,
+    authentication::{
+        keycloak_auth::{KeycloakRequest, LoginOptions},
+        roles,
+        token_provider::default_provider,
+    },
+    cli::{
+        alias,
+        args::{
+            AliasArgs, AuthArgs, BaseCommands, COMMAND_ROLES, ClubArgs, CompletionsArgs, ManArgs,
+            PluginsArgs, VibingCliParser,
+        },
+        session::Session,
+        shell,
+    },
+    client::VibingClient,
+    config::Config,
+    creds::{
+        self, accounts,
+        record::{CredentialRecord, describe_timestamp},
+    },
+    env,
+    error::{AppError, Result, build_generic_error},
+    features::club::{
+        cache,
+        lookup::{self, MatchKind},
+    },
+    interactive,
+    logging::{self, LogLevel, LogOptions},
+    output::{self, OutputOptions},
+    plugins::{self, Plugin},
+    requests::{
+        PageDirection, PageOptions, cassette,
+        trace::{self, TraceOptions},
+    },
+    tui, watch,
 };
-use clap::Parser;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, error::ErrorKind};
+use clap_complete::env::Shells;
+use serde_json::json;
+use std::{fs, io, iter};
+use tracing::{info, warn};
+
+/// Environment variable through which the shell asks vibing for completions
+pub const COMPLETE_ENV: &str = "COMPLETE";
 
 pub async fn handle_cli_args() -> Result<()> {
-    let args: VibingCliParser = VibingCliParser::parse();
-    let verbose_flag = args.verbose;
+    logging::init(LogOptions::startup())?;
+    let args = alias::expand(std::env::args().skip(1).collect())?;
+    let matches =
+        build_command(args.clone()).get_matches_from(iter::once("vibing".to_owned()).chain(args));
+    let mut session = Session::new();
+    if let Some(("shell", _)) = matches.subcommand() {
+        let args = parse_args(&matches)?;
+        session.profile = args.profile;
+        interactive::require_interactive("The shell")?;
+        return shell::run(&mut session).await;
+    }
+    dispatch(&matches, &mut session).await
+}
+
+/// The command line parser, with the role annotations of the commands in the help
+pub fn build_command(args: impl IntoIterator<Item = String>) -> Command {
+    let command = override_repeated_options(VibingCliParser::command());
+    let is_help_requested = is_help_requested(command.clone(), args);
+    annotate_roles(command, is_help_requested)
+}
+
+/// Whether clap answers the arguments with a help page, e.g. `club --help` or `help club` but
+/// not `club get help`
+fn is_help_requested(command: Command, args: impl IntoIterator<Item = String>) -> bool {
+    command
+        .try_get_matches_from(iter::once("vibing".to_owned()).chain(args))
+        .is_err_and(|err| {
+            matches!(
+                err.kind(),
+                ErrorKind::DisplayHelp | ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand
+            )
+        })
+}
 
-    match args.command {
-        BaseCommands::Auth(auth_commands) => match_auth_args(&auth_commands, verbose_flag).await?,
-        BaseCommands::Club(club_args) => match_club_args(&club_args, verbose_flag).await?,
+/// Lets the last occurrence of an option win, so that the options given to an alias override the
+/// ones of its command
+fn override_repeated_options(command: Command) -> Command {
+    let mut command = command.args_override_self(true);
+    for subcommand in command.get_subcommands_mut() {
+        *subcommand = override_repeated_options(std::mem::take(subcommand));
+    }
+    command
+}
+
+/// Runs the parsed command line within the session
+pub async fn dispatch(matches: &ArgMatches, session: &mut Session) -> Result<()> {
+    let args = parse_args(matches)?;
+    // As for the aliases, an unreadable configuration must not prevent the commands fixing it
+    let config = Config::load().unwrap_or_else(|err| {
+        warn!(
+            "The configuration is ignored, it cannot be read ({}).",
+            err.message().lines().next().unwrap_or_default()
+        );
+        Config::default()
+    });
+    logging::init(LogOptions {
+        level: args
+            .log_level
+            .or(LogLevel::from_verbosity(args.verbose))
+            .or(config.log_level)
+            .unwrap_or_default(),
+        format: args.log_format.or(config.log_format).unwrap_or_default(),
+        file: args.log_file || config.log_file.unwrap_or(false),
+        // Logs would be drawn over the screen
+        stderr: !matches!(args.command, BaseCommands::Tui),
+        http: args.trace_http || args.print_curl,
+    })?;
+    trace::init(TraceOptions {
+        trace_http: args.trace_http,
+        print_curl: args.print_curl,
+    });
+    let template = match &args.template_file {
+        // Editors end files with a newline, which `println!` already adds
+        Some(template_file) => Some(
+            fs::read_to_string(template_file)
+                .map_err(|err| {
+                    AppError::TemplateError(format!(
+                        "Could not read the template file `{}`: {err}",
+                        template_file.display()
+                    ))
+                })?
+                .trim_end_matches(['\n', '\r'])
+                .to_owned(),
+        ),
+        None => args.template.clone(),
+    };
+    output::init(OutputOptions {
+        format: args.output,
+        fields: args.fields.clone(),
+        query: args.query.clone(),
+        template,
+    });
+    accounts::select_profile(args.profile.or_else(|| session.profile.clone()));
+    interactive::set_non_interactive(args.non_interactive);
+    if let Some(role) = required_role(matches) {
+        roles::require_role(role)?;
+    }
+    if let Some(cassette_dir) = &args.record {
+        cassette::record_to(cassette_dir)?;
+    }
+    if let Some(cassette_dir) = &args.replay {
+        cassette::replay_from(cassette_dir)?;
+    }
+
+    let result = match args.command {
+        BaseCommands::Auth(auth_commands) => {
+            let result = match_auth_args(&auth_commands).await;
+            session.invalidate();
+            result
+        }
+        BaseCommands::Club(club_args) => match_club_args(&club_args, &session.client()?).await,
+        BaseCommands::Credential(credential_args) => {
+            creds::helper::run(
+                credential_args.operation(),
+                io::stdin().lock(),
+                io::stdout(),
+            )
+            .await
+        }
+        BaseCommands::Completions(completions_args) => print_completions(&completions_args),
+        BaseCommands::Man(man_args) => generate_man(&man_args),
+        BaseCommands::Shell => {
+            println!("You are already in the vibing shell");
+            Ok(())
+        }
+        BaseCommands::Alias(alias_args) => match_alias_args(&alias_args),
+        BaseCommands::Plugins(PluginsArgs::List) => list_plugins(),
+        BaseCommands::External(external_args) => run_plugin(&external_args, session).await,
+        BaseCommands::Tui => match interactive::require_interactive("The terminal UI") {
+            Ok(()) => tui::run(session.client()?).await,
+            Err(err) => Err(err),
+        },
+    };
+    if args.record.is_some() || args.replay.is_some() {
+        cassette::eject();
+    }
+    result
+}
+
+fn match_alias_args(alias_args: &AliasArgs) -> Result<()> {
+    match alias_args {
+        AliasArgs::Set(alias_set_args) => {
+            alias::set(
+                alias_set_args.name.as_str(),
+                alias_set_args.command_line().as_str(),
+            )?;
+            let alias = json!({
+                "name": alias_set_args.name,
+                "command": alias_set_args.command_line(),
+            });
+            output::print(&alias, |_| {
+                println!(
+                    "`vibing {}` now runs `vibing {}`",
+                    alias_set_args.name,
+                    alias_set_args.command_line()
+                )
+            })?;
+        }
+        AliasArgs::List => {
+            let aliases = Config::load()?.aliases;
+            output::print(&aliases, |aliases| {
+                if aliases.is_empty() {
+                    println!(
+                        "No alias is defined, run `vibing alias set <name> <command>` to add one."
+                    );
+                }
+                for (name, command) in aliases {
+                    println!("{name} = {command}");
+                }
+            })?;
+        }
+        AliasArgs::Remove(alias_remove_args) => {
+            alias::remove(alias_remove_args.name.as_str())?;
+            let alias = json!({ "name": alias_remove_args.name, "removed": true });
+            output::print(&alias, |_| {
+                println!("Removed the `{}` alias", alias_remove_args.name)
+            })?;
+        }
+    }
+    Ok(())
+}
+
+fn list_plugins() -> Result<()> {
+    let installed_plugins: Vec<Plugin> = plugins::discover().into_values().collect();
+    output::print(&installed_plugins, |installed_plugins| {
+        if installed_plugins.is_empty() {
+            println!(
+                "No plugin is installed, plugins are executables named `vibing-<command>` on PATH."
+            );
+            return;
+        }
+        println!("Installed plugins:");
+        for plugin in installed_plugins {
+            println!("  {}\t{}", plugin.name, plugin.path.display());
+        }
+    })
+}
+
+/// Runs `vibing <name> <args>` as the `vibing-<name>` plugin
+async fn run_plugin(external_args: &[String], session: &mut Session) -> Result<()> {
+    let Some((name, args)) = external_args.split_first() else {
+        return Err(AppError::UnknownCommand(String::new()));
+    };
+    let plugin =
+        plugins::find(name.as_str()).ok_or_else(|| AppError::UnknownCommand(name.clone()))?;
+    let exit_code = plugins::run(&plugin, args, &session.client()?).await?;
+    if exit_code != 0 {
+        return Err(AppError::PluginFailed(name.clone(), exit_code));
     }
     Ok(())
 }
 
-async fn match_auth_args(command: &AuthArgs, verbose: bool) -> Result<()> {
-    let auth_request = KeycloakRequest::new();
+fn parse_args(matches: &ArgMatches) -> Result<VibingCliParser> {
+    VibingCliParser::from_arg_matches(matches).map_err(|err| AppError::Other(Box::new(err)))
+}
+
+/// Marks the commands requiring a role in the help, flagging the ones the current user lacks
+/// when help is being requested
+fn annotate_roles(mut command: Command, is_help_requested: bool) -> Command {
+    let current_roles = if is_help_requested {
+        roles::current_roles()
+    } else {
+        None
+    };
+    for (path, role) in COMMAND_ROLES {
+        let is_missing = current_roles
+            .as_ref()
+            .is_some_and(|current_roles| !current_roles.iter().any(|granted| granted == role));
+        let note = if is_missing {
+            format!("[requires role {role}, which you do not have]")
+        } else {
+            format!("[requires role {role}]")
+        };
+        command = annotate_subcommand(command, path, note.as_str());
+    }
+    command
+}
+
+fn annotate_subcommand(command: Command, path: &[&str], note: &str) -> Command {
+    let Some((name, rest)) = path.split_first() else {
+        let about = command
+            .get_about()
+            .map(|about| format!("{about} {note}"))
+            .unwrap_or_else(|| note.to_owned());
+        return command.about(about);
+    };
+    command.mut_subcommand(*name, |subcommand| {
+        annotate_subcommand(subcommand, rest, note)
+    })
+}
+
+/// The role declared in [`COMMAND_ROLES`] for the invoked subcommand
+fn required_role(matches: &ArgMatches) -> Option<&'static str> {
+    let mut path = Vec::new();
+    let mut current_matches = matches;
+    while let Some((name, subcommand_matches)) = current_matches.subcommand() {
+        path.push(name);
+        current_matches = subcommand_matches;
+    }
+    COMMAND_ROLES
+        .iter()
+        .find(|(role_path, _)| *role_path == path.as_slice())
+        .map(|(_, role)| *role)
+}
+
+async fn match_auth_args(command: &AuthArgs) -> Result<()> {
     match command {
-        AuthArgs::Login => {
-            auth_request
+        AuthArgs::Login(login_args) => {
+            interactive::require_interactive("Logging in")?;
+            let config = Config::load()?;
+            let login_options = LoginOptions {
+                show_qr: !login_args.no_qr,
+                invert_qr: login_args.qr_invert.or(config.qr_invert).unwrap_or(true),
+                open_browser: !login_args.no_browser && config.open_browser.unwrap_or(true),
+                offline: login_args.offline,
+            };
+            let authentication = KeycloakRequest::new()?
                 .authenticate(
-                    env::get_env("VIBING_DEVICE_CODE_CLIENT_ID").as_str(),
-                    env::get_env("VIBING_CLIENT_SECRET").as_str(),
-                    verbose,
+                    env::get_env("VIBING_DEVICE_CODE_CLIENT_ID")?.as_str(),
+                    env::get_env("VIBING_CLIENT_SECRET")?.as_str(),
+                    &login_options,
                 )
                 .await?;
+            info!("Storing credential");
+            let record = CredentialRecord::from_authentication(&authentication);
+            let account = login_args
+                .account
+                .clone()
+                .or_else(|| record.username.clone())
+                .unwrap_or_else(|| accounts::DEFAULT_ACCOUNT.to_owned());
+            let profile = accounts::current_profile()?;
+            accounts::add_account(profile.as_str(), account.as_str(), &record)?;
+            println!("Successfully authenticated as `{account}`! Now you can vibe ~");
         }
-        AuthArgs::Logout => {
-            verbose_print(verbose, "Logging you out...");
-            let credential_store_service = env::get_env("CREDENTIAL_STORE_AUTH_SERVICE");
-            let credential_store_user = env::get_env("CREDENTIAL_STORE_AUTH_USER");
-            let access_token = creds::get_cred(
-                credential_store_service.as_str(),
-                credential_store_user.as_str(),
-            )?;
-            auth_request
-                .revoke_token(
-                    env::get_env("VIBING_DEVICE_CODE_CLIENT_ID").as_str(),
-                    env::get_env("VIBING_CLIENT_SECRET").as_str(),
-                    access_token.as_str(),
-                    verbose,
-                )
-                .await?;
-            println!("Successfully logged out!");
-            verbose_print(verbose, "Removing credential...");
-            creds::remove_cred(
-                &credential_store_service.as_str(),
-                credential_store_user.as_str(),
-            )?;
-            verbose_print(verbose, "Done!");
+        AuthArgs::Logout(logout_args) => {
+            info!("Logging you out...");
+            let mut logged_in_accounts = Vec::new();
+            if logout_args.all_profiles {
+                for profile in accounts::profiles()? {
+                    for account in accounts::accounts(profile.as_str())?.accounts {
+                        logged_in_accounts.push((profile.clone(), account));
+                    }
+                }
+            } else {
+                let profile = accounts::current_profile()?;
+                let account = accounts::accounts(profile.as_str())?
+                    .active_account
+                    .ok_or(AppError::CredentialsNotFound)?;
+                logged_in_accounts.push((profile, account));
+            }
+            let mut failures = Vec::new();
+            for (profile, account) in logged_in_accounts {
+                failures.extend(
+                    logout_account(logout_args.local_only, profile.as_str(), account.as_str())
+                        .await,
+                );
+            }
+            if !failures.is_empty() {
+                return Err(AppError::LogoutFailed(failures));
+            }
         }
         AuthArgs::Check => {
-            verbose_print(verbose, "Checking authentication status...");
-            // TODO: Handle NoEntry Creds error
-            let access_token = creds::get_cred(
-                env::get_env("CREDENTIAL_STORE_AUTH_SERVICE").as_str(),
-                env::get_env("CREDENTIAL_STORE_AUTH_USER").as_str(),
-            )?;
-            let is_authenticated = auth_request
+            info!("Checking authentication status...");
+            let (is_logged_in, is_authenticated) = match creds::get_cred_use_defaults() {
+                Ok(access_token) => {
+                    let is_authenticated = KeycloakRequest::new()?
+                        .check_auth(
+                            env::get_env("VIBING_DEVICE_CODE_CLIENT_ID")?.as_str(),
+                            env::get_env("VIBING_CLIENT_SECRET")?.as_str(),
+                            access_token.as_str(),
+                        )
+                        .await?;
+                    (true, is_authenticated)
+                }
+                Err(AppError::CredentialsNotFound) => (false, false),
+                Err(err) => return Err(err),
+            };
+            let auth_status = json!({
+                "loggedIn": is_logged_in,
+                "authenticated": is_authenticated,
+            });
+            output::print(&auth_status, |_| {
+                if !is_logged_in {
+                    println!("You are not logged in, please run 'vibing auth login' to login.");
+                } else if !is_authenticated {
+                    println!(
+                        "You are not authenticated or your token is expired, please run 'vibing auth login' to login again."
+                    );
+                } else {
+                    println!("You are authenticated and ready to make requests!");
+                }
+            })?;
+        }
+        AuthArgs::Storage => {
+            let configured_store = Config::load()?.credential_store;
+            let store_in_use = creds::open_store()?.describe();
+            let storage = json!({
+                "configured": configured_store,
+                "inUse": store_in_use,
+            });
+            output::print(&storage, |_| {
+                println!("Configured storage: {configured_store:?}");
+                println!("Storage in use: {store_in_use}");
+            })?;
+        }
+        AuthArgs::Token => {
+            let access_token = default_provider().token().await?;
+            let is_authenticated = KeycloakRequest::new()?
                 .check_auth(
-                    env::get_env("VIBING_DEVICE_CODE_CLIENT_ID").as_str(),
-                    env::get_env("VIBING_CLIENT_SECRET").as_str(),
+                    env::get_env("VIBING_DEVICE_CODE_CLIENT_ID")?.as_str(),
+                    env::get_env("VIBING_CLIENT_SECRET")?.as_str(),
                     access_token.as_str(),
                 )
                 .await?;
             if !is_authenticated {
+                return Err(AppError::TokenUnavailable(
+                    "The stored access token is expired, please run 'vibing auth login' to login again."
+                        .to_owned(),
+                ));
+            }
+            output::print(&json!({ "accessToken": access_token }), |_| {
+                println!("{access_token}")
+            })?;
+        }
+        AuthArgs::Info => {
+            let record = creds::get_record_use_defaults()?;
+            output::print(&record.info(), |_| {
+                let unknown = || "unknown".to_owned();
                 println!(
-                    "You are not authenticated or your token is expired, please run 'vibing auth login' to login again."
+                    "Username: {}",
+                    record.username.clone().unwrap_or_else(unknown)
                 );
-                return Ok(());
-            }
-            println!("You are authenticated and ready to make requests!");
+                println!(
+                    "Subject: {}",
+                    record.subject.clone().unwrap_or_else(unknown)
+                );
+                println!("Issuer: {}", record.issuer.clone().unwrap_or_else(unknown));
+                println!("Scopes: {}", record.scopes.join(" "));
+                println!(
+                    "Offline session: {}",
+                    if record.is_offline() { "yes" } else { "no" }
+                );
+                match record.access_expires_at {
+                    Some(expires_at) if record.is_access_expired(0) => {
+                        println!("Access token: expired {}", describe_timestamp(expires_at))
+                    }
+                    Some(expires_at) => {
+                        println!("Access token: expires {}", describe_timestamp(expires_at))
+                    }
+                    None => println!("Access token: expiry unknown"),
+                }
+                match (&record.refresh_token, record.refresh_expires_at) {
+                    (None, _) => println!("Refresh token: none"),
+                    (Some(_), None) => println!("Refresh token: does not expire"),
+                    (Some(_), Some(expires_at)) if record.is_refresh_expired() => {
+                        println!("Refresh token: expired {}", describe_timestamp(expires_at))
+                    }
+                    (Some(_), Some(expires_at)) => {
+                        println!("Refresh token: expires {}", describe_timestamp(expires_at))
+                    }
+                }
+            })?;
+        }
+        AuthArgs::Accounts => {
+            let profile = accounts::current_profile()?;
+            let profile_config = accounts::accounts(profile.as_str())?;
+            let profile_accounts = json!({
+                "profile": profile,
+                "activeAccount": profile_config.active_account,
+                "accounts": profile_config.accounts,
+            });
+            output::print(&profile_accounts, |_| {
+                if profile_config.accounts.is_empty() {
+                    println!(
+                        "No account is logged in within the `{profile}` profile, please run 'vibing auth login' to login."
+                    );
+                    return;
+                }
+                println!("Accounts of the `{profile}` profile:");
+                for account in &profile_config.accounts {
+                    let marker = if profile_config.active_account.as_ref() == Some(account) {
+                        "*"
+                    } else {
+                        " "
+                    };
+                    println!("{marker} {account}");
+                }
+            })?;
+        }
+        AuthArgs::Switch(switch_args) => {
+            let profile = accounts::current_profile()?;
+            accounts::switch_account(profile.as_str(), switch_args.user.as_str())?;
+            let profile_accounts = json!({
+                "profile": profile,
+                "activeAccount": switch_args.user,
+            });
+            output::print(&profile_accounts, |_| {
+                println!("Now using `{}`", switch_args.user)
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Ends the account's session on the server, then forgets it locally even when that failed.
+/// Returns the failed steps, so that the other accounts are still logged out.
+async fn logout_account(local_only: bool, profile: &str, account: &str) -> Vec<(String, AppError)> {
+    let label = format!("`{account}` ({profile} profile)");
+    let mut failures = Vec::new();
+    if !local_only {
+        match end_session(profile, account).await {
+            Ok(()) => (),
+            Err(AppError::LogoutFailed(steps)) => failures.extend(
+                steps
+                    .into_iter()
+                    .map(|(step, err)| (format!("{label}, {step}"), err)),
+            ),
+            Err(err) => failures.push((format!("{label}, ending the session"), err)),
+        }
+    }
+    info!("Removing credential...");
+    match accounts::remove_account(profile, account) {
+        Ok(()) if failures.is_empty() => {
+            println!("Successfully logged out from {label}!");
+        }
+        Ok(()) => println!("Removed the local credentials of {label}."),
+        Err(err) => failures.push((format!("{label}, removing the local credentials"), err)),
+    }
+    failures
+}
+
+/// Logs the account's session out of Keycloak, nothing being left to end without credentials
+async fn end_session(profile: &str, account: &str) -> Result<()> {
+    let (service, user) = accounts::credential_entry(profile, account)?;
+    let record = match creds::get_record(service.as_str(), user.as_str()) {
+        Ok(record) => record,
+        Err(AppError::CredentialsNotFound) => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    KeycloakRequest::new()?
+        .logout(
+            env::get_env("VIBING_DEVICE_CODE_CLIENT_ID")?.as_str(),
+            env::get_env("VIBING_CLIENT_SECRET")?.as_str(),
+            record.access_token.as_str(),
+            record.refresh_token.as_deref(),
+        )
+        .await
+}
+
+fn print_completions(completions_args: &CompletionsArgs) -> Result<()> {
+    let mut command = VibingCliParser::command();
+    let bin_name = command.get_name().to_owned();
+    if completions_args.static_script {
+        clap_complete::generate(
+            completions_args.shell,
+            &mut command,
+            bin_name,
+            &mut io::stdout(),
+        );
+        return Ok(());
+    }
+    // The script calls back into vibing with `COMPLETE=<shell>`, handled in `main`
+    let shell_name = completions_args.shell.to_string();
+    let shells = Shells::builtins();
+    let completer = shells.completer(shell_name.as_str()).ok_or_else(|| {
+        AppError::Other(Box::new(build_generic_error(Some(
+            format!("Dynamic completion is not supported for {shell_name}").as_str(),
+        ))))
+    })?;
+    completer.write_registration(
+        COMPLETE_ENV,
+        bin_name.as_str(),
+        bin_name.as_str(),
+        bin_name.as_str(),
+        &mut io::stdout(),
+    )?;
+    Ok(())
+}
+
+fn generate_man(man_args: &ManArgs) -> Result<()> {
+    let command = VibingCliParser::command();
+    match &man_args.out_dir {
+        Some(out_dir) => {
+            fs::create_dir_all(out_dir)?;
+            clap_mangen::generate_to(command, out_dir)?;
+            output::print(&json!({ "outDir": out_dir }), |_| {
+                println!("Manual pages written to {}", out_dir.display())
+            })?;
+        }
+        None => {
+            output::require_human_readable("The manual page")?;
+            clap_mangen::Man::new(command).render(&mut io::stdout())?
         }
     }
     Ok(())
 }
 
-async fn match_club_args(club_args: &ClubArgs, verbose_flag: bool) -> Result<()> {
+/// The ID of the club, which may be given by name, asking the user to choose when several
+/// clubs match and to confirm a club only named like it
+async fn resolve_club(club: &str, exact: bool, client: &VibingClient) -> Result<u32> {
+    if let Ok(club_id) = club.trim().parse::<u32>() {
+        return Ok(club_id);
+    }
+    let Some(matches) = lookup::search(client, club, exact).await? else {
+        return Err(AppError::ClubNotFound(club.to_owned()));
+    };
+    let candidates: Vec<String> = matches
+        .clubs
+        .iter()
+        .map(|(club_id, cached_club)| {
+            format!(
+                "{} (ID {club_id}, {})",
+                cached_club.club_name, cached_club.address
+            )
+        })
+        .collect();
+    match matches.clubs.as_slice() {
+        [] => Err(AppError::ClubNotFound(club.to_owned())),
+        [(club_id, _)] if matches.kind == MatchKind::Exact => Ok(*club_id),
+        [(club_id, _)] => {
+            let candidate = candidates[0].clone();
+            if !interactive::can_prompt() {
+                return Err(AppError::InexactClubMatch(club.to_owned(), candidate));
+            }
+            if interactive::confirm(
+                format!("No club is named `{club}`, use {candidate}?").as_str(),
+            )? {
+                Ok(*club_id)
+            } else {
+                Err(AppError::ClubNotFound(club.to_owned()))
+            }
+        }
+        _ if exact || !interactive::can_prompt() => {
+            Err(AppError::AmbiguousClub(club.to_owned(), candidates))
+        }
+        _ => {
+            let choice = interactive::choose(
+                format!("Several clubs match `{club}`:").as_str(),
+                &candidates,
+            )?;
+            Ok(matches.clubs[choice].0)
+        }
+    }
+}
+
+async fn match_club_args(club_args: &ClubArgs, client: &VibingClient) -> Result<()> {
     match club_args {
         ClubArgs::Get(club_get_args) => {
-            get_club_info(club_get_args.club_id, verbose_flag).await?;
+            let club_id =
+                resolve_club(club_get_args.club.as_str(), club_get_args.exact, client).await?;
+            if let Some(watch_options) = club_get_args
+                .watch
+                .options(format!("vibing club get {club_id}"))?
+            {
+                return watch::watch(watch_options, || client.get_club(club_id)).await;
+            }
+            let club_response = client.get_club(club_id).await?;
+            cache::remember(std::slice::from_ref(&club_response));
+            output::print(&club_response, |club_response| {
+                println!("{club_response:#?}")
+            })?;
+        }
+        ClubArgs::List(club_list_args) => {
+            let page_options = PageOptions::new(
+                club_list_args.page,
+                club_list_args.size,
+                club_list_args.sort.clone(),
+                club_list_args.direction.map(PageDirection::from),
+                Some(club_list_args.total),
+            );
+            if let Some(watch_options) = club_list_args
+                .watch
+                .options("vibing club list".to_owned())?
+            {
+                return watch::watch(watch_options, || client.list_clubs(&page_options)).await;
+            }
+            let clubs_page = client.list_clubs(&page_options).await?;
+            cache::remember(&clubs_page.content);
+            output::print(&clubs_page, |clubs_page| println!("{clubs_page:#?}"))?;
+        }
+        ClubArgs::Delete(club_delete_args) => {
+            client.delete_club(club_delete_args.club_id).await?;
+            cache::forget(club_delete_args.club_id);
+            let deleted_club = json!({ "id": club_delete_args.club_id, "deleted": true });
+            output::print(&deleted_club, |_| {
+                println!("Club {} deleted", club_delete_args.club_id)
+            })?;
         }
     }
     Ok(())
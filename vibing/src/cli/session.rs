@@ -0,0 +1,50 @@
+use reqwest::Client;
+use std::sync::Arc;
+
+use crate::{
+    authentication::token_provider::{CachedTokenProvider, TokenProvider, default_provider},
+    client::VibingClient,
+    creds::accounts,
+    error::Result,
+};
+
+/// State kept between the commands run by the same process, e.g. within `vibing shell`
+#[derive(Default)]
+pub struct Session {
+    /// Profile used by the commands not passing `--profile`
+    pub profile: Option<String>,
+    /// Token provider of the profile it was created for
+    token_provider: Option<(String, Arc<dyn TokenProvider>)>,
+    http_client: Client,
+}
+
+impl Session {
+    pub fn new() -> Session {
+        Session::default()
+    }
+
+    /// Builds a client sharing the HTTP connections and the cached token of the session
+    pub fn client(&mut self) -> Result<VibingClient> {
+        let profile = accounts::current_profile()?;
+        let token_provider = match &self.token_provider {
+            Some((cached_profile, token_provider)) if *cached_profile == profile => {
+                token_provider.clone()
+            }
+            _ => {
+                let token_provider: Arc<dyn TokenProvider> =
+                    Arc::new(CachedTokenProvider::new(default_provider()));
+                self.token_provider = Some((profile, token_provider.clone()));
+                token_provider
+            }
+        };
+        VibingClient::builder()
+            .shared_token_provider(token_provider)
+            .http_client(self.http_client.clone())
+            .build()
+    }
+
+    /// Forgets the cached token, e.g. after logging in or switching account
+    pub fn invalidate(&mut self) {
+        self.token_provider = None;
+    }
+}
@@ -0,0 +1,155 @@
+use clap::CommandFactory;
+use rustyline::{
+    Context, Editor, Helper, Highlighter, Hinter, Validator,
+    completion::{Completer, Pair},
+    error::ReadlineError,
+    history::FileHistory,
+};
+use std::{ffi::OsString, fs, iter};
+
+use crate::{
+    cli::{
+        alias,
+        args::VibingCliParser,
+        matcher::{build_command, dispatch},
+        session::Session,
+    },
+    config,
+    creds::accounts::{self, DEFAULT_PROFILE},
+    error::{AppError, Result, handle},
+};
+
+const HISTORY_FILE: &str = "history.txt";
+
+/// Completes the line as the shell completion scripts do, cached club IDs included
+#[derive(Helper, Hinter, Highlighter, Validator)]
+struct ShellHelper;
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let line = &line[..pos];
+        let Some(mut words) = shlex::split(line) else {
+            return Ok((pos, Vec::new()));
+        };
+        if line.is_empty() || line.ends_with(char::is_whitespace) {
+            words.push(String::new());
+        }
+        let current_word_len = words.last().map(String::len).unwrap_or_default();
+        let args: Vec<OsString> = iter::once("vibing".to_owned())
+            .chain(words)
+            .map(OsString::from)
+            .collect();
+        let arg_index = args.len() - 1;
+        let candidates =
+            clap_complete::engine::complete(&mut VibingCliParser::command(), args, arg_index, None)
+                .unwrap_or_default();
+        let pairs = candidates
+            .into_iter()
+            .filter(|candidate| !candidate.is_hide_set())
+            .map(|candidate| {
+                let value = candidate.get_value().to_string_lossy().into_owned();
+                let display = match candidate.get_help() {
+                    Some(help) => format!("{value}  ({help})"),
+                    None => value.clone(),
+                };
+                Pair {
+                    display,
+                    replacement: value,
+                }
+            })
+            .collect();
+        Ok((pos.saturating_sub(current_word_len), pairs))
+    }
+}
+
+/// Reads commands until `exit` or EOF, running them within the same session so that the HTTP
+/// client and the access token are reused
+pub async fn run(session: &mut Session) -> Result<()> {
+    let mut editor = Editor::<ShellHelper, FileHistory>::new().map_err(readline_error)?;
+    editor.set_helper(Some(ShellHelper));
+    let history_path = config::data_dir()?.join(HISTORY_FILE);
+    let _ = editor.load_history(&history_path);
+    println!(
+        "Type vibing commands without the `vibing` prefix, `help` to list them, `profile [name]` to show or change the profile and `exit` to quit."
+    );
+    loop {
+        // Commands passing `--profile` only change it for themselves
+        accounts::select_profile(session.profile.clone());
+        let line = match editor.readline(prompt().as_str()) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(err) => return Err(readline_error(err)),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line);
+        let Some(words) = shlex::split(line) else {
+            eprintln!("Unbalanced quotes in the command");
+            continue;
+        };
+        match words.as_slice() {
+            [command] if command == "exit" || command == "quit" => break,
+            [command] if command == "profile" => {
+                println!("{}", accounts::current_profile()?);
+                continue;
+            }
+            [command, profile] if command == "profile" => {
+                session.profile = Some(profile.clone());
+                session.invalidate();
+                println!("Now using the `{profile}` profile");
+                continue;
+            }
+            _ => (),
+        }
+        let words = match alias::expand(words) {
+            Ok(words) => words,
+            Err(err) => {
+                handle(err);
+                continue;
+            }
+        };
+        let matches = match build_command(words.clone())
+            .try_get_matches_from(iter::once("vibing".to_owned()).chain(words))
+        {
+            Ok(matches) => matches,
+            Err(err) => {
+                let _ = err.print();
+                continue;
+            }
+        };
+        if let Err(err) = dispatch(&matches, session).await {
+            handle(err);
+        }
+    }
+    if let Some(parent) = history_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let _ = editor.save_history(&history_path);
+    Ok(())
+}
+
+/// `vibing (<profile>/<account>)> `, leaving out the account when none is logged in
+fn prompt() -> String {
+    let profile = accounts::current_profile().unwrap_or_else(|_| DEFAULT_PROFILE.to_owned());
+    let active_account = accounts::accounts(profile.as_str())
+        .ok()
+        .and_then(|profile_config| profile_config.active_account);
+    match active_account {
+        Some(account) => format!("vibing ({profile}/{account})> "),
+        None => format!("vibing ({profile})> "),
+    }
+}
+
+fn readline_error(err: ReadlineError) -> AppError {
+    AppError::Other(Box::new(err))
+}
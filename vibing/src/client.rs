@@ -0,0 +1,118 @@
+use reqwest::{Client, Method, RequestBuilder};
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+
+use crate::{
+    authentication::token_provider::{StaticTokenProvider, TokenProvider, default_provider},
+    env,
+    error::{AppError, Result, build_generic_error},
+    requests::{execute, into_result},
+};
+
+/// Typed client for the Vibes backend.
+///
+/// Resource-specific methods live next to their feature (e.g. `get_club` in
+/// `features::club::club_requests`).
+#[derive(Clone)]
+pub struct VibingClient {
+    base_url: String,
+    token_provider: Arc<dyn TokenProvider>,
+    http_client: Client,
+}
+
+#[derive(Default)]
+pub struct VibingClientBuilder {
+    base_url: Option<String>,
+    token_provider: Option<Arc<dyn TokenProvider>>,
+    http_client: Option<Client>,
+}
+
+impl VibingClientBuilder {
+    pub fn new() -> VibingClientBuilder {
+        VibingClientBuilder::default()
+    }
+
+    /// Defaults to the `BACKEND_URL` environment variable
+    pub fn base_url(mut self, base_url: impl Into<String>) -> VibingClientBuilder {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Defaults to [`default_provider`]
+    pub fn token_provider(
+        mut self,
+        token_provider: impl TokenProvider + 'static,
+    ) -> VibingClientBuilder {
+        self.token_provider = Some(Arc::new(token_provider));
+        self
+    }
+
+    pub fn shared_token_provider(
+        mut self,
+        token_provider: Arc<dyn TokenProvider>,
+    ) -> VibingClientBuilder {
+        self.token_provider = Some(token_provider);
+        self
+    }
+
+    /// Authorizes every request with the given token
+    pub fn token(self, token: impl Into<String>) -> VibingClientBuilder {
+        self.token_provider(StaticTokenProvider::new(token))
+    }
+
+    pub fn http_client(mut self, http_client: Client) -> VibingClientBuilder {
+        self.http_client = Some(http_client);
+        self
+    }
+
+    pub fn build(self) -> Result<VibingClient> {
+        let base_url = match self.base_url {
+            Some(base_url) => base_url,
+            None => env::get_env("BACKEND_URL")?,
+        };
+        if base_url.trim().is_empty() {
+            return Err(AppError::Other(Box::new(build_generic_error(Some(
+                "The backend URL cannot be empty",
+            )))));
+        }
+        Ok(VibingClient {
+            base_url: base_url.trim().trim_end_matches('/').to_owned(),
+            token_provider: self.token_provider.unwrap_or_else(default_provider),
+            http_client: self.http_client.unwrap_or_default(),
+        })
+    }
+}
+
+impl VibingClient {
+    pub fn builder() -> VibingClientBuilder {
+        VibingClientBuilder::new()
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// A valid access token, refreshed if needed, e.g. to hand over to another program
+    pub async fn access_token(&self) -> Result<String> {
+        self.token_provider.token().await
+    }
+
+    /// Prepares an authorized request to the given backend path
+    pub async fn request(&self, method: Method, path: &str) -> Result<RequestBuilder> {
+        let token = self.token_provider.token().await?;
+        Ok(self
+            .http_client
+            .request(method, format!("{}{}", self.base_url, path.trim()))
+            .bearer_auth(token))
+    }
+
+    /// Sends the request and deserializes the successful response
+    pub async fn send<U>(&self, req: RequestBuilder) -> Result<U>
+    where
+        U: DeserializeOwned,
+    {
+        let req = req.build()?;
+        let res = execute(&self.http_client, req).await?;
+        into_result::<U>(res)
+    }
+}
@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use crate::{
+    error::{AppError, Result, build_generic_error},
+    logging::{LogFormat, LogLevel},
+};
+
+/// Environment variable overriding the path of the configuration file
+pub const CONFIG_ENV: &str = "VIBING_CONFIG";
+
+const APP_DIR: &str = "vibing";
+const CONFIG_FILE: &str = "config.toml";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialStoreKind {
+    /// Use the OS secure storage, falling back to the encrypted file when it is unavailable
+    #[default]
+    Auto,
+    /// Always use the OS secure storage
+    Keyring,
+    /// Always use the encrypted file
+    File,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Config {
+    /// Where credentials are stored
+    pub credential_store: CredentialStoreKind,
+    /// Path of the encrypted credentials file, defaults to `<data dir>/vibing/credentials.enc`
+    pub credential_file: Option<PathBuf>,
+    /// Path of the key file used to encrypt the credentials file when no passphrase is set,
+    /// defaults to `<config dir>/vibing/credentials.key`
+    pub credential_key_file: Option<PathBuf>,
+    /// Open the login link in the browser, defaults to `true` (skipped anyway on headless sessions)
+    pub open_browser: Option<bool>,
+    /// Print the login QR code light on dark, defaults to `true`; set to `false` on light
+    /// terminal themes
+    pub qr_invert: Option<bool>,
+    /// Warn this many days before an offline session expires, defaults to 7
+    pub offline_expiry_warning_days: Option<u32>,
+    /// Profile used when neither `--profile` nor `VIBING_PROFILE` is given
+    pub default_profile: Option<String>,
+    /// Accounts logged in within each profile
+    pub profiles: BTreeMap<String, ProfileConfig>,
+    /// Commands run by other names, e.g. `open = "club list --total"`
+    pub aliases: BTreeMap<String, String>,
+    /// Level of the logs when neither `-v` nor `--log-level` is given, defaults to `warn`
+    pub log_level: Option<LogLevel>,
+    /// Format of the logs when `--log-format` is not given, defaults to `text`
+    pub log_format: Option<LogFormat>,
+    /// Also write the logs to daily rotated files in `<state dir>/vibing/logs`, like `--log-file`
+    pub log_file: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ProfileConfig {
+    /// Account whose token is used for the requests
+    pub active_account: Option<String>,
+    pub accounts: Vec<String>,
+}
+
+impl Config {
+    /// Reads the configuration file, falling back to the defaults when it does not exist
+    pub fn load() -> Result<Config> {
+        let path = config_path()?;
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+        let content = fs::read_to_string(&path)?;
+        toml::from_str::<Config>(&content).map_err(|err| {
+            AppError::ConfigError(format!(
+                "Invalid configuration in `{}`: {err}",
+                path.display()
+            ))
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = config_path()?;
+        let content = toml::to_string_pretty(self).map_err(|err| {
+            AppError::ConfigError(format!("Could not save the configuration: {err}"))
+        })?;
+        path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&path, content))
+            .map_err(|err| {
+                AppError::ConfigError(format!(
+                    "Could not save the configuration to `{}`: {err}",
+                    path.display()
+                ))
+            })
+    }
+}
+
+pub fn config_path() -> Result<PathBuf> {
+    if let Ok(path) = std::env::var(CONFIG_ENV)
+        && !path.trim().is_empty()
+    {
+        return Ok(PathBuf::from(path));
+    }
+    Ok(config_dir()?.join(CONFIG_FILE))
+}
+
+/// `$XDG_CONFIG_HOME/vibing` or the platform equivalent
+pub fn config_dir() -> Result<PathBuf> {
+    dirs::config_dir()
+        .map(|dir| dir.join(APP_DIR))
+        .ok_or_else(|| missing_dir("configuration"))
+}
+
+/// `$XDG_DATA_HOME/vibing` or the platform equivalent
+pub fn data_dir() -> Result<PathBuf> {
+    dirs::data_dir()
+        .map(|dir| dir.join(APP_DIR))
+        .ok_or_else(|| missing_dir("data"))
+}
+
+/// `$XDG_CACHE_HOME/vibing` or the platform equivalent
+pub fn cache_dir() -> Result<PathBuf> {
+    dirs::cache_dir()
+        .map(|dir| dir.join(APP_DIR))
+        .ok_or_else(|| missing_dir("cache"))
+}
+
+/// `$XDG_STATE_HOME/vibing`, or the local data directory on platforms without one
+pub fn state_dir() -> Result<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join(APP_DIR))
+        .ok_or_else(|| missing_dir("state"))
+}
+
+fn missing_dir(kind: &str) -> AppError {
+    AppError::Other(Box::new(build_generic_error(Some(
+        format!("Could not determine the {kind} directory of the current user").as_str(),
+    ))))
+}
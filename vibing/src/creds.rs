@@ -1,29 +1,81 @@
-use keyring::Entry;
+pub mod accounts;
+pub mod file_store;
+pub mod helper;
+pub mod keyring_store;
+pub mod record;
 
-use crate::{env, error::Result};
+use crate::{
+    config::{Config, CredentialStoreKind},
+    creds::{
+        file_store::EncryptedFileStore, keyring_store::KeyringStore, record::CredentialRecord,
+    },
+    error::Result,
+};
+use tracing::debug;
 
-// TODO: Get serivce from function argument
-pub fn store_cred(user: &str, password: &str) -> Result<()> {
-    let entry = Entry::new("vibing", user)?;
-    entry.set_password(password).unwrap();
-    println!("Credential stored successfully");
+/// A backend able to persist secrets by service and user
+pub trait CredentialStore {
+    /// Where the credentials are kept, for display purposes
+    fn describe(&self) -> String;
+    fn get(&self, service: &str, user: &str) -> Result<String>;
+    fn set(&self, service: &str, user: &str, password: &str) -> Result<()>;
+    fn delete(&self, service: &str, user: &str) -> Result<()>;
+}
+
+/// Opens the credential store selected by the configuration.
+///
+/// In `auto` mode the OS secure storage is preferred, falling back to the encrypted file only
+/// when the system has none (e.g. on headless Linux without a Secret Service). A locked keyring
+/// or a dismissed unlock prompt fails instead.
+pub fn open_store() -> Result<Box<dyn CredentialStore>> {
+    let config = Config::load()?;
+    let use_keyring = match config.credential_store {
+        CredentialStoreKind::Keyring => true,
+        CredentialStoreKind::File => false,
+        CredentialStoreKind::Auto => keyring_store::is_available()?,
+    };
+    if use_keyring {
+        return Ok(Box::new(KeyringStore));
+    }
+    Ok(Box::new(EncryptedFileStore::from_config(&config)?))
+}
+
+pub fn store_cred(service: &str, user: &str, password: &str) -> Result<()> {
+    open_store()?.set(service, user, password)?;
+    debug!("Credential stored");
     Ok(())
 }
 
 pub fn get_cred(service: &str, user: &str) -> Result<String> {
-    let entry = Entry::new(service, user)?;
-    Ok(entry.get_password()?)
+    open_store()?.get(service, user)
 }
 
-/// Uses default service and user to obtain authentication token from secure storage
+/// Obtains the access token of the active account of the current profile from secure storage
 pub fn get_cred_use_defaults() -> Result<String> {
-    let service = env::get_env("CREDENTIAL_STORE_AUTH_SERVICE");
-    let user = env::get_env("CREDENTIAL_STORE_AUTH_USER");
-    get_cred(service.as_str(), user.as_str())
+    Ok(get_record_use_defaults()?.access_token)
+}
+
+pub fn store_record(service: &str, user: &str, record: &CredentialRecord) -> Result<()> {
+    store_cred(service, user, record.to_secret()?.as_str())
+}
+
+/// Reads the login record, migrating credentials saved by older versions as a bare token
+pub fn get_record(service: &str, user: &str) -> Result<CredentialRecord> {
+    let store = open_store()?;
+    let (record, is_legacy) = CredentialRecord::parse(store.get(service, user)?.as_str())?;
+    if is_legacy {
+        // Failing to migrate is not fatal, the legacy format will simply be parsed again
+        let _ = store.set(service, user, record.to_secret()?.as_str());
+    }
+    Ok(record)
+}
+
+/// Obtains the login record of the active account of the current profile from secure storage
+pub fn get_record_use_defaults() -> Result<CredentialRecord> {
+    let (service, user) = accounts::active_entry()?;
+    get_record(service.as_str(), user.as_str())
 }
 
 pub fn remove_cred(service: &str, user: &str) -> Result<()> {
-    let entry = Entry::new(service, user)?;
-    entry.delete_credential()?;
-    Ok(())
+    open_store()?.delete(service, user)
 }
@@ -0,0 +1,232 @@
+use std::sync::RwLock;
+
+use crate::{
+    config::{Config, ProfileConfig},
+    creds::{self, record::CredentialRecord},
+    env,
+    error::{AppError, Result},
+};
+
+/// Environment variable selecting the profile when `--profile` is not given
+pub const PROFILE_ENV: &str = "VIBING_PROFILE";
+pub const DEFAULT_PROFILE: &str = "default";
+/// Account name used when the token does not carry a username
+pub const DEFAULT_ACCOUNT: &str = "default";
+
+/// Profile selected for the whole process (e.g. through `--profile`)
+static SELECTED_PROFILE: RwLock<Option<String>> = RwLock::new(None);
+
+pub fn select_profile(profile: Option<String>) {
+    *SELECTED_PROFILE
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = profile;
+}
+
+/// The selected profile, then `VIBING_PROFILE`, then the configured default profile
+pub fn current_profile() -> Result<String> {
+    let selected_profile = SELECTED_PROFILE
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    if let Some(profile) = selected_profile {
+        return Ok(profile);
+    }
+    if let Ok(profile) = std::env::var(PROFILE_ENV)
+        && !profile.trim().is_empty()
+    {
+        return Ok(profile.trim().to_owned());
+    }
+    Ok(Config::load()?
+        .default_profile
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_owned()))
+}
+
+/// The credential store user under which an account's login is saved
+pub fn credential_user(profile: &str, username: &str) -> String {
+    format!("{profile}/{username}")
+}
+
+/// Lists the accounts logged in within the profile.
+///
+/// The single login saved by older versions shows as the only account of the default profile,
+/// it is moved there by the first change to the accounts (see [`migrate_legacy_login`]).
+pub fn accounts(profile: &str) -> Result<ProfileConfig> {
+    let config = Config::load()?;
+    if let Some(profile_config) = config.profiles.get(profile) {
+        return Ok(profile_config.clone());
+    }
+    Ok(match legacy_account(&config, profile)? {
+        Some(username) => ProfileConfig {
+            active_account: Some(username.clone()),
+            accounts: vec![username],
+        },
+        None => ProfileConfig::default(),
+    })
+}
+
+/// Names of the profiles having at least one account logged in
+pub fn profiles() -> Result<Vec<String>> {
+    let config = Config::load()?;
+    let mut profiles: Vec<String> = config
+        .profiles
+        .iter()
+        .filter(|(_, profile_config)| !profile_config.accounts.is_empty())
+        .map(|(profile, _)| profile.clone())
+        .collect();
+    if legacy_account(&config, DEFAULT_PROFILE)?.is_some() {
+        profiles.push(DEFAULT_PROFILE.to_owned());
+        profiles.sort();
+    }
+    Ok(profiles)
+}
+
+/// Returns the credential store service and user of the active account of the current profile
+pub fn active_entry() -> Result<(String, String)> {
+    let profile = current_profile()?;
+    let profile_config = accounts(profile.as_str())?;
+    let username = profile_config
+        .active_account
+        .ok_or_else(|| AppError::CredentialsNotFound)?;
+    credential_entry(profile.as_str(), username.as_str())
+}
+
+/// Returns the credential store service and user under which the account's login is saved
+pub fn credential_entry(profile: &str, username: &str) -> Result<(String, String)> {
+    let service = env::get_env("CREDENTIAL_STORE_AUTH_SERVICE")?;
+    if legacy_account(&Config::load()?, profile)?.as_deref() == Some(username) {
+        return Ok((service, env::get_env("CREDENTIAL_STORE_AUTH_USER")?));
+    }
+    Ok((service, credential_user(profile, username)))
+}
+
+/// Saves the login of an account, making it the active one of the profile
+pub fn add_account(profile: &str, username: &str, record: &CredentialRecord) -> Result<()> {
+    save_account(profile, username, record, true)
+}
+
+/// Saves a login obtained elsewhere (e.g. through the credential helper), only making it the
+/// active account when the profile has none
+pub fn import_account(profile: &str, username: &str, record: &CredentialRecord) -> Result<()> {
+    save_account(profile, username, record, false)
+}
+
+fn save_account(
+    profile: &str,
+    username: &str,
+    record: &CredentialRecord,
+    activate: bool,
+) -> Result<()> {
+    migrate_legacy_login()?;
+    creds::store_record(
+        env::get_env("CREDENTIAL_STORE_AUTH_SERVICE")?.as_str(),
+        credential_user(profile, username).as_str(),
+        record,
+    )?;
+    let mut config = Config::load()?;
+    let profile_config = config.profiles.entry(profile.to_owned()).or_default();
+    if !profile_config
+        .accounts
+        .iter()
+        .any(|account| account == username)
+    {
+        profile_config.accounts.push(username.to_owned());
+    }
+    if activate || profile_config.active_account.is_none() {
+        profile_config.active_account = Some(username.to_owned());
+    }
+    config.save()
+}
+
+pub fn switch_account(profile: &str, username: &str) -> Result<()> {
+    migrate_legacy_login()?;
+    let mut config = Config::load()?;
+    let profile_config = config.profiles.entry(profile.to_owned()).or_default();
+    if !profile_config
+        .accounts
+        .iter()
+        .any(|account| account == username)
+    {
+        return Err(AppError::ConfigError(format!(
+            "No account `{username}` is logged in within the `{profile}` profile, run 'vibing auth accounts' to list them."
+        )));
+    }
+    profile_config.active_account = Some(username.to_owned());
+    config.save()
+}
+
+/// Forgets the account, activating the first remaining one of the profile
+pub fn remove_account(profile: &str, username: &str) -> Result<()> {
+    migrate_legacy_login()?;
+    match creds::remove_cred(
+        env::get_env("CREDENTIAL_STORE_AUTH_SERVICE")?.as_str(),
+        credential_user(profile, username).as_str(),
+    ) {
+        Ok(()) | Err(AppError::CredentialsNotFound) => (),
+        Err(err) => return Err(err),
+    }
+    let mut config = Config::load()?;
+    let profile_config = config.profiles.entry(profile.to_owned()).or_default();
+    profile_config
+        .accounts
+        .retain(|account| account != username);
+    if profile_config.active_account.as_deref() == Some(username) {
+        profile_config.active_account = profile_config.accounts.first().cloned();
+    }
+    config.save()
+}
+
+/// The account name of the login saved by older versions, while the default profile is not
+/// configured yet
+fn legacy_account(config: &Config, profile: &str) -> Result<Option<String>> {
+    if profile != DEFAULT_PROFILE || config.profiles.contains_key(DEFAULT_PROFILE) {
+        return Ok(None);
+    }
+    Ok(legacy_record()?.map(|record| {
+        record
+            .username
+            .unwrap_or_else(|| DEFAULT_ACCOUNT.to_owned())
+    }))
+}
+
+fn legacy_record() -> Result<Option<CredentialRecord>> {
+    match creds::get_record(
+        env::get_env("CREDENTIAL_STORE_AUTH_SERVICE")?.as_str(),
+        env::get_env("CREDENTIAL_STORE_AUTH_USER")?.as_str(),
+    ) {
+        Ok(record) => Ok(Some(record)),
+        Err(AppError::CredentialsNotFound) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Moves the single login saved by older versions into the default profile. Run before changing
+/// the accounts, so that reading them never writes the configuration.
+pub fn migrate_legacy_login() -> Result<()> {
+    let mut config = Config::load()?;
+    if config.profiles.contains_key(DEFAULT_PROFILE) {
+        return Ok(());
+    }
+    let Some(record) = legacy_record()? else {
+        return Ok(());
+    };
+    let service = env::get_env("CREDENTIAL_STORE_AUTH_SERVICE")?;
+    let legacy_user = env::get_env("CREDENTIAL_STORE_AUTH_USER")?;
+    let username = record
+        .username
+        .clone()
+        .unwrap_or_else(|| DEFAULT_ACCOUNT.to_owned());
+    creds::store_record(
+        service.as_str(),
+        credential_user(DEFAULT_PROFILE, username.as_str()).as_str(),
+        &record,
+    )?;
+    creds::remove_cred(service.as_str(), legacy_user.as_str())?;
+    config.profiles.insert(
+        DEFAULT_PROFILE.to_owned(),
+        ProfileConfig {
+            active_account: Some(username.clone()),
+            accounts: vec![username],
+        },
+    );
+    config.save()
+}
@@ -0,0 +1,314 @@
+use argon2::Argon2;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use chacha20poly1305::{
+    ChaCha20Poly1305, Key, KeyInit, Nonce,
+    aead::{Aead, Generate},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config::{self, Config},
+    creds::CredentialStore,
+    error::{AppError, Result},
+};
+
+/// Environment variable holding the passphrase the credentials file is encrypted with
+pub const PASSPHRASE_ENV: &str = "VIBING_CREDENTIAL_PASSPHRASE";
+
+const FORMAT_VERSION: u32 = 1;
+const CREDENTIAL_FILE: &str = "credentials.enc";
+const KEY_FILE: &str = "credentials.key";
+
+/// Secrets grouped by service, then by user
+type Secrets = BTreeMap<String, BTreeMap<String, String>>;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeySource {
+    Passphrase,
+    KeyFile,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct EncryptedFile {
+    version: u32,
+    key_source: KeySource,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Keeps credentials in a ChaCha20-Poly1305 encrypted file, for machines without a keyring.
+///
+/// The key is derived with Argon2id from `VIBING_CREDENTIAL_PASSPHRASE` when set, otherwise
+/// from a random key file which is created on first use.
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    key_file: PathBuf,
+}
+
+impl EncryptedFileStore {
+    pub fn new(path: impl Into<PathBuf>, key_file: impl Into<PathBuf>) -> EncryptedFileStore {
+        EncryptedFileStore {
+            path: path.into(),
+            key_file: key_file.into(),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Result<EncryptedFileStore> {
+        let path = match &config.credential_file {
+            Some(path) => path.clone(),
+            None => config::data_dir()?.join(CREDENTIAL_FILE),
+        };
+        let key_file = match &config.credential_key_file {
+            Some(key_file) => key_file.clone(),
+            None => config::config_dir()?.join(KEY_FILE),
+        };
+        Ok(EncryptedFileStore::new(path, key_file))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn key_source(&self) -> KeySource {
+        match std::env::var(PASSPHRASE_ENV) {
+            Ok(passphrase) if !passphrase.is_empty() => KeySource::Passphrase,
+            _ => KeySource::KeyFile,
+        }
+    }
+
+    fn key_material(&self, key_source: KeySource, create: bool) -> Result<Vec<u8>> {
+        if key_source == KeySource::Passphrase {
+            return std::env::var(PASSPHRASE_ENV)
+                .map(|passphrase| passphrase.into_bytes())
+                .map_err(|_| {
+                    store_error(format!(
+                        "The credentials file is encrypted with a passphrase, set `{PASSPHRASE_ENV}` to unlock it"
+                    ))
+                });
+        }
+        if self.key_file.exists() {
+            return Ok(fs::read(&self.key_file)?);
+        }
+        if !create {
+            return Err(store_error(format!(
+                "The key file `{}` of the credentials file is missing",
+                self.key_file.display()
+            )));
+        }
+        let key_material = <[u8; 32]>::generate().to_vec();
+        write_private(&self.key_file, &key_material)?;
+        Ok(key_material)
+    }
+
+    fn read(&self) -> Result<Secrets> {
+        if !self.path.exists() {
+            return Ok(Secrets::new());
+        }
+        let encrypted_file = serde_json::from_slice::<EncryptedFile>(&fs::read(&self.path)?)?;
+        if encrypted_file.version != FORMAT_VERSION {
+            return Err(store_error(format!(
+                "Unsupported credentials file version {}",
+                encrypted_file.version
+            )));
+        }
+        let key_material = self.key_material(encrypted_file.key_source, false)?;
+        let salt = decode(&encrypted_file.salt)?;
+        let nonce = Nonce::try_from(decode(&encrypted_file.nonce)?.as_slice())
+            .map_err(|_| store_error("The credentials file is corrupted".to_owned()))?;
+        let cipher = ChaCha20Poly1305::new(&derive_key(&key_material, &salt)?);
+        let plaintext = cipher
+            .decrypt(&nonce, decode(&encrypted_file.ciphertext)?.as_slice())
+            .map_err(|_| {
+                store_error(
+                    "Could not decrypt the credentials file, the passphrase or key file is wrong"
+                        .to_owned(),
+                )
+            })?;
+        Ok(serde_json::from_slice::<Secrets>(&plaintext)?)
+    }
+
+    fn write(&self, secrets: &Secrets) -> Result<()> {
+        let key_source = self.key_source();
+        let key_material = self.key_material(key_source, true)?;
+        let salt = <[u8; 16]>::generate();
+        let nonce = Nonce::generate();
+        let cipher = ChaCha20Poly1305::new(&derive_key(&key_material, &salt)?);
+        let ciphertext = cipher
+            .encrypt(&nonce, serde_json::to_vec(secrets)?.as_slice())
+            .map_err(|_| store_error("Could not encrypt the credentials file".to_owned()))?;
+        let encrypted_file = EncryptedFile {
+            version: FORMAT_VERSION,
+            key_source,
+            salt: STANDARD.encode(salt),
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        };
+        write_private(&self.path, &serde_json::to_vec_pretty(&encrypted_file)?)
+    }
+}
+
+impl CredentialStore for EncryptedFileStore {
+    fn describe(&self) -> String {
+        let key_source = match self.key_source() {
+            KeySource::Passphrase => format!("passphrase from `{PASSPHRASE_ENV}`"),
+            KeySource::KeyFile => format!("key file `{}`", self.key_file.display()),
+        };
+        format!("encrypted file `{}` ({key_source})", self.path.display())
+    }
+
+    fn get(&self, service: &str, user: &str) -> Result<String> {
+        self.read()?
+            .get(service)
+            .and_then(|users| users.get(user))
+            .cloned()
+            .ok_or(AppError::CredentialsNotFound)
+    }
+
+    fn set(&self, service: &str, user: &str, password: &str) -> Result<()> {
+        let mut secrets = self.read()?;
+        secrets
+            .entry(service.to_owned())
+            .or_default()
+            .insert(user.to_owned(), password.to_owned());
+        self.write(&secrets)
+    }
+
+    fn delete(&self, service: &str, user: &str) -> Result<()> {
+        let mut secrets = self.read()?;
+        let removed = secrets
+            .get_mut(service)
+            .and_then(|users| users.remove(user));
+        if removed.is_none() {
+            return Err(AppError::CredentialsNotFound);
+        }
+        secrets.retain(|_, users| !users.is_empty());
+        self.write(&secrets)
+    }
+}
+
+fn derive_key(key_material: &[u8], salt: &[u8]) -> Result<Key> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(key_material, salt, &mut key)
+        .map_err(|err| store_error(format!("Could not derive the encryption key: {err}")))?;
+    Ok(key)
+}
+
+fn decode(value: &str) -> Result<Vec<u8>> {
+    STANDARD
+        .decode(value)
+        .map_err(|_| store_error("The credentials file is corrupted".to_owned()))
+}
+
+/// Writes the file so that only the current user can read it, through a temporary file renamed
+/// over it so that an interrupted write never leaves it truncated
+fn write_private(path: &Path, content: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file_name = path.file_name().unwrap_or_default().to_owned();
+    file_name.push(".tmp");
+    let temp_path = path.with_file_name(file_name);
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&temp_path)?;
+    // The mode only applies to created files, a leftover temporary file keeps its own
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(content)?;
+    file.sync_all()?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+fn store_error(reason: String) -> AppError {
+    AppError::CredentialStoreError(reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A store in its own temporary directory, left to the system to clean up
+    fn store(name: &str) -> (EncryptedFileStore, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("vibing-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let store = EncryptedFileStore::new(dir.join(CREDENTIAL_FILE), dir.join(KEY_FILE));
+        (store, dir)
+    }
+
+    #[test]
+    fn round_trips_the_secrets() {
+        let (store, dir) = store("round-trip");
+        assert!(matches!(
+            store.get("vibing", "alice"),
+            Err(AppError::CredentialsNotFound)
+        ));
+        store.set("vibing", "alice", "first").unwrap();
+        store.set("vibing", "bob", "second").unwrap();
+        store.set("vibing", "alice", "third").unwrap();
+        assert_eq!(store.get("vibing", "alice").unwrap(), "third");
+        assert_eq!(store.get("vibing", "bob").unwrap(), "second");
+        let content = fs::read_to_string(store.path()).unwrap();
+        assert!(!content.contains("third") && !content.contains("alice"));
+        store.delete("vibing", "alice").unwrap();
+        assert!(matches!(
+            store.get("vibing", "alice"),
+            Err(AppError::CredentialsNotFound)
+        ));
+        assert!(matches!(
+            store.delete("vibing", "alice"),
+            Err(AppError::CredentialsNotFound)
+        ));
+        let mut entries: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        entries.sort();
+        assert_eq!(entries, [CREDENTIAL_FILE, KEY_FILE]);
+    }
+
+    #[test]
+    fn fails_to_decrypt_with_another_key() {
+        let (store, dir) = store("other-key");
+        store.set("vibing", "alice", "secret").unwrap();
+        fs::write(dir.join(KEY_FILE), [0; 32]).unwrap();
+        assert!(matches!(
+            store.get("vibing", "alice"),
+            Err(AppError::CredentialStoreError(_))
+        ));
+        fs::remove_file(dir.join(KEY_FILE)).unwrap();
+        assert!(matches!(
+            store.get("vibing", "alice"),
+            Err(AppError::CredentialStoreError(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn writes_files_only_the_user_can_read() {
+        use std::os::unix::fs::PermissionsExt;
+        let (store, dir) = store("permissions");
+        store.set("vibing", "alice", "secret").unwrap();
+        for file in [CREDENTIAL_FILE, KEY_FILE] {
+            let mode = fs::metadata(dir.join(file)).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600, "{file}");
+        }
+    }
+}
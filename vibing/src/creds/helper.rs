@@ -0,0 +1,111 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, Write},
+};
+
+use crate::{
+    authentication::token_provider::{KeyringTokenProvider, TokenProvider},
+    creds::{self, accounts, record::CredentialRecord},
+    env,
+    error::{AppError, Result},
+};
+
+/// Runs one operation of the credential helper protocol.
+///
+/// Like `git credential`, the request is read as `key=value` lines terminated by a blank line
+/// or EOF. Recognised keys are `service`, `username` and `password`, `username` being the name
+/// of an account of the current profile; `service` can only be vibing's own and `username`
+/// defaults to the active account. `get` answers with the same format, refreshing the token
+/// when needed, and prints nothing when no credential is stored.
+///
+/// `store` saves a bare access token as an account, refusing to replace a login made with
+/// `vibing auth login` whose refresh token would be lost.
+pub async fn run(operation: &str, input: impl BufRead, mut output: impl Write) -> Result<()> {
+    let attributes = read_attributes(input)?;
+    let service = env::get_env("CREDENTIAL_STORE_AUTH_SERVICE")?;
+    if let Some(requested_service) = attributes.get("service")
+        && *requested_service != service
+    {
+        return Err(AppError::CredentialHelperError(format!(
+            "Only vibing's own `{service}` service is available, not `{requested_service}`."
+        )));
+    }
+    let profile = accounts::current_profile()?;
+    let profile_config = accounts::accounts(profile.as_str())?;
+    let username = match attributes.get("username") {
+        Some(username) => username.clone(),
+        None => match profile_config.active_account.clone() {
+            Some(active_account) => active_account,
+            // Nothing to get or erase without a login
+            None if matches!(operation, "get" | "erase") => return Ok(()),
+            None => accounts::DEFAULT_ACCOUNT.to_owned(),
+        },
+    };
+    let is_logged_in = profile_config.accounts.contains(&username);
+    match operation {
+        "get" if !is_logged_in => (),
+        "get" => {
+            let (service, user) = accounts::credential_entry(profile.as_str(), username.as_str())?;
+            match KeyringTokenProvider::new(service.as_str(), user.as_str())
+                .token()
+                .await
+            {
+                Ok(password) => {
+                    writeln!(output, "service={service}")?;
+                    writeln!(output, "username={username}")?;
+                    writeln!(output, "password={password}")?;
+                }
+                Err(AppError::CredentialsNotFound) => (),
+                Err(err) => return Err(err),
+            }
+        }
+        "store" => {
+            let password = attributes.get("password").ok_or_else(|| {
+                AppError::CredentialHelperError(
+                    "The `password` attribute is required to store a credential.".to_owned(),
+                )
+            })?;
+            if is_logged_in {
+                let (service, user) =
+                    accounts::credential_entry(profile.as_str(), username.as_str())?;
+                match creds::get_record(service.as_str(), user.as_str()) {
+                    Ok(record) if record.refresh_token.is_some() => {
+                        return Err(AppError::CredentialHelperError(format!(
+                            "`{username}` logged in with `vibing auth login`, run `vibing auth logout` before storing another credential for it."
+                        )));
+                    }
+                    Ok(_) | Err(AppError::CredentialsNotFound) => (),
+                    Err(err) => return Err(err),
+                }
+            }
+            accounts::import_account(
+                profile.as_str(),
+                username.as_str(),
+                &CredentialRecord::from_access_token(password.as_str()),
+            )?;
+        }
+        "erase" if !is_logged_in => (),
+        "erase" => accounts::remove_account(profile.as_str(), username.as_str())?,
+        _ => {
+            return Err(AppError::CredentialHelperError(format!(
+                "Unknown credential operation `{operation}`, expected `get`, `store` or `erase`."
+            )));
+        }
+    }
+    output.flush()?;
+    Ok(())
+}
+
+fn read_attributes(input: impl BufRead) -> Result<HashMap<String, String>> {
+    let mut attributes = HashMap::new();
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            break;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            attributes.insert(key.trim().to_owned(), value.to_owned());
+        }
+    }
+    Ok(attributes)
+}
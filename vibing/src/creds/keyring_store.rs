@@ -0,0 +1,198 @@
+use keyring::Entry;
+use std::error::Error;
+use tracing::debug;
+
+use crate::{
+    creds::CredentialStore,
+    error::{AppError, Result},
+    interactive,
+};
+
+const PROBE_SERVICE: &str = "vibing";
+const PROBE_USER: &str = "vibing-availability-probe";
+
+/// D-Bus errors meaning that no session bus or Secret Service provider is running
+#[cfg(target_os = "linux")]
+const UNAVAILABLE_DBUS_ERRORS: [&str; 6] = [
+    "org.freedesktop.DBus.Error.ServiceUnknown",
+    "org.freedesktop.DBus.Error.NameHasNoOwner",
+    "org.freedesktop.DBus.Error.NoServer",
+    "org.freedesktop.DBus.Error.NotSupported",
+    "org.freedesktop.DBus.Error.FileNotFound",
+    "org.freedesktop.DBus.Error.Spawn.ExecFailed",
+];
+
+/// Re-enables the keychain prompts when dropped
+#[cfg(target_os = "macos")]
+type PromptGuard = security_framework::os::macos::keychain::KeychainUserInteractionLock;
+#[cfg(not(target_os = "macos"))]
+struct PromptGuard;
+
+/// Why the OS secure storage could not be used
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyringFailure {
+    /// There is no secure storage on this system, e.g. no Secret Service on headless Linux
+    Unavailable,
+    /// The storage is locked and was not unlocked
+    Locked,
+    /// The user dismissed the unlock prompt
+    Cancelled,
+    /// Any other failure, e.g. a D-Bus timeout
+    Other,
+}
+
+/// Keeps credentials in the OS secure storage (Keychain, Credential Manager, Secret Service)
+pub struct KeyringStore;
+
+impl CredentialStore for KeyringStore {
+    fn describe(&self) -> String {
+        "OS secure storage (keyring)".to_owned()
+    }
+
+    fn get(&self, service: &str, user: &str) -> Result<String> {
+        let _prompt_guard = forbid_unlock_prompts()?;
+        let entry = Entry::new(service, user)?;
+        Ok(entry.get_password()?)
+    }
+
+    fn set(&self, service: &str, user: &str, password: &str) -> Result<()> {
+        let _prompt_guard = forbid_unlock_prompts()?;
+        let entry = Entry::new(service, user)?;
+        entry.set_password(password)?;
+        Ok(())
+    }
+
+    fn delete(&self, service: &str, user: &str) -> Result<()> {
+        let _prompt_guard = forbid_unlock_prompts()?;
+        let entry = Entry::new(service, user)?;
+        entry.delete_credential()?;
+        Ok(())
+    }
+}
+
+/// Checks whether the OS secure storage exists on this system (e.g. a Secret Service is running
+/// on D-Bus).
+///
+/// Only a missing storage counts as unavailable: a locked keyring, a dismissed prompt or a
+/// transient failure are returned as errors, so that credentials never silently move to another
+/// store.
+pub fn is_available() -> Result<bool> {
+    let _prompt_guard = forbid_unlock_prompts()?;
+    let probe = Entry::new(PROBE_SERVICE, PROBE_USER).and_then(|entry| entry.get_password());
+    match probe {
+        Ok(_) | Err(keyring::Error::NoEntry) => Ok(true),
+        Err(err) if classify(&err) == Some(KeyringFailure::Unavailable) => {
+            debug!("The OS secure storage is unavailable: {err}");
+            Ok(false)
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Keeps the OS from asking to unlock the storage while the guard lives when vibing runs
+/// non-interactively, so that a locked storage fails with `CredentialStoreLocked` instead of
+/// waiting for a prompt nobody answers
+fn forbid_unlock_prompts() -> Result<Option<PromptGuard>> {
+    if interactive::is_interactive() {
+        return Ok(None);
+    }
+    forbid_platform_unlock_prompts().map(Some)
+}
+
+/// The Secret Service has no such switch: the default collection, where the credentials are
+/// stored, is checked beforehand since the keyring unlocks it with a prompt
+#[cfg(target_os = "linux")]
+fn forbid_platform_unlock_prompts() -> Result<PromptGuard> {
+    use dbus_secret_service::{EncryptionType, SecretService};
+    // Without a Secret Service, the keyring operation itself reports the storage as unavailable
+    let Ok(secret_service) = SecretService::connect(EncryptionType::Plain) else {
+        return Ok(PromptGuard);
+    };
+    let is_locked = secret_service
+        .get_default_collection()
+        .and_then(|collection| collection.is_locked());
+    if let Ok(true) = is_locked {
+        return Err(AppError::CredentialStoreLocked(
+            "the default Secret Service collection is locked".to_owned(),
+        ));
+    }
+    Ok(PromptGuard)
+}
+
+#[cfg(target_os = "macos")]
+fn forbid_platform_unlock_prompts() -> Result<PromptGuard> {
+    use security_framework::os::macos::keychain::SecKeychain;
+    SecKeychain::disable_user_interaction()
+        .map_err(|err| AppError::CredentialStoreError(err.to_string()))
+}
+
+/// The Credential Manager never prompts
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn forbid_platform_unlock_prompts() -> Result<PromptGuard> {
+    Ok(PromptGuard)
+}
+
+/// Tells why a keyring operation failed from the error of the platform, `None` for the errors
+/// which are not about reaching the storage (e.g. a missing entry)
+pub fn classify(err: &keyring::Error) -> Option<KeyringFailure> {
+    match err {
+        keyring::Error::NoStorageAccess(platform_err)
+        | keyring::Error::PlatformFailure(platform_err) => {
+            Some(classify_platform_error(platform_err.as_ref()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn classify_platform_error(err: &(dyn Error + Send + Sync + 'static)) -> KeyringFailure {
+    use dbus_secret_service::Error as SecretServiceError;
+    match err.downcast_ref::<SecretServiceError>() {
+        Some(SecretServiceError::Locked) => KeyringFailure::Locked,
+        Some(SecretServiceError::Prompt) => KeyringFailure::Cancelled,
+        Some(SecretServiceError::Unavailable) => KeyringFailure::Unavailable,
+        Some(SecretServiceError::Dbus(dbus_err))
+            if dbus_err
+                .name()
+                .is_some_and(|name| UNAVAILABLE_DBUS_ERRORS.contains(&name)) =>
+        {
+            KeyringFailure::Unavailable
+        }
+        _ => KeyringFailure::Other,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn classify_platform_error(err: &(dyn Error + Send + Sync + 'static)) -> KeyringFailure {
+    match err
+        .downcast_ref::<security_framework::base::Error>()
+        .map(|err| err.code())
+    {
+        // errSecUserCanceled
+        Some(-128) => KeyringFailure::Cancelled,
+        // errSecInteractionNotAllowed, returned for locked keychains when prompts are disabled
+        Some(-25308) => KeyringFailure::Locked,
+        // errSecNotAvailable, errSecNoSuchKeychain, errSecInvalidKeychain
+        Some(-25291 | -25294 | -25295) => KeyringFailure::Unavailable,
+        _ => KeyringFailure::Other,
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn classify_platform_error(err: &(dyn Error + Send + Sync + 'static)) -> KeyringFailure {
+    match err
+        .downcast_ref::<keyring::windows::Error>()
+        .map(|err| err.0)
+    {
+        // ERROR_CANCELLED
+        Some(1223) => KeyringFailure::Cancelled,
+        // ERROR_NO_SUCH_LOGON_SESSION
+        Some(1312) => KeyringFailure::Unavailable,
+        _ => KeyringFailure::Other,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn classify_platform_error(_: &(dyn Error + Send + Sync + 'static)) -> KeyringFailure {
+    KeyringFailure::Other
+}
@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    authentication::{
+        jwt::decode_claims, keycloak_auth::OFFLINE_SCOPE,
+        response::KeycloakSuccessfulAuthentication,
+    },
+    error::Result,
+};
+
+/// Current version of the stored credential format
+pub const RECORD_VERSION: u32 = 1;
+
+/// What vibing persists in the credential store after a login.
+///
+/// Timestamps are seconds since the Unix epoch.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CredentialRecord {
+    pub version: u32,
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub access_expires_at: Option<i64>,
+    pub refresh_expires_at: Option<i64>,
+    pub issuer: Option<String>,
+    pub subject: Option<String>,
+    pub username: Option<String>,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+/// What can be shown about a login, leaving the tokens out
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialInfo {
+    pub username: Option<String>,
+    pub subject: Option<String>,
+    pub issuer: Option<String>,
+    pub scopes: Vec<String>,
+    pub offline: bool,
+    pub access_expires_at: Option<i64>,
+    pub has_refresh_token: bool,
+    pub refresh_expires_at: Option<i64>,
+}
+
+impl CredentialRecord {
+    pub fn from_authentication(
+        authentication: &KeycloakSuccessfulAuthentication,
+    ) -> CredentialRecord {
+        let now = now();
+        let mut record = CredentialRecord::from_access_token(authentication.access_token.as_str());
+        record.access_expires_at = Some(now + i64::from(authentication.expires_in));
+        record.refresh_token = authentication.refresh_token.clone();
+        // Keycloak uses 0 for refresh tokens without a session lifespan (e.g. offline tokens),
+        // whose claims may still carry the idle expiry
+        record.refresh_expires_at = authentication
+            .refresh_expires_in
+            .filter(|refresh_expires_in| *refresh_expires_in > 0)
+            .map(|refresh_expires_in| now + i64::from(refresh_expires_in))
+            .or_else(|| {
+                authentication
+                    .refresh_token
+                    .as_deref()
+                    .and_then(decode_claims)
+                    .and_then(|claims| claims.exp)
+            });
+        if record.scopes.is_empty() {
+            record.scopes = authentication
+                .scope
+                .split_whitespace()
+                .map(str::to_owned)
+                .collect();
+        }
+        record
+    }
+
+    /// Builds a record out of a bare access token, reading what it can from its claims
+    pub fn from_access_token(access_token: &str) -> CredentialRecord {
+        let claims = decode_claims(access_token).unwrap_or_default();
+        CredentialRecord {
+            version: RECORD_VERSION,
+            access_token: access_token.to_owned(),
+            refresh_token: None,
+            access_expires_at: claims.exp,
+            refresh_expires_at: None,
+            scopes: claims.scopes(),
+            issuer: claims.iss,
+            subject: claims.sub,
+            username: claims.preferred_username,
+        }
+    }
+
+    /// Parses a stored secret, returning whether it was in the legacy bare-token format
+    pub fn parse(secret: &str) -> Result<(CredentialRecord, bool)> {
+        if !secret.trim_start().starts_with('{') {
+            return Ok((CredentialRecord::from_access_token(secret.trim()), true));
+        }
+        Ok((serde_json::from_str::<CredentialRecord>(secret)?, false))
+    }
+
+    pub fn to_secret(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Whether the access token expires within `margin` seconds; unknown expiries never do
+    pub fn is_access_expired(&self, margin: i64) -> bool {
+        self.access_expires_at
+            .is_some_and(|expires_at| expires_at <= now() + margin)
+    }
+
+    pub fn is_refresh_expired(&self) -> bool {
+        self.refresh_expires_at
+            .is_some_and(|expires_at| expires_at <= now())
+    }
+
+    /// Whether the refresh token is an offline token, surviving the end of the SSO session
+    pub fn is_offline(&self) -> bool {
+        self.scopes.iter().any(|scope| scope == OFFLINE_SCOPE)
+    }
+
+    pub fn info(&self) -> CredentialInfo {
+        CredentialInfo {
+            username: self.username.clone(),
+            subject: self.subject.clone(),
+            issuer: self.issuer.clone(),
+            scopes: self.scopes.clone(),
+            offline: self.is_offline(),
+            access_expires_at: self.access_expires_at,
+            has_refresh_token: self.refresh_token.is_some(),
+            refresh_expires_at: self.refresh_expires_at,
+        }
+    }
+
+    /// Applies a token refresh, keeping the metadata the response does not carry
+    pub fn refreshed(&self, authentication: &KeycloakSuccessfulAuthentication) -> CredentialRecord {
+        let mut record = CredentialRecord::from_authentication(authentication);
+        if record.refresh_token.is_none() {
+            record.refresh_token = self.refresh_token.clone();
+            record.refresh_expires_at = self.refresh_expires_at;
+        }
+        record.issuer = record.issuer.or(self.issuer.clone());
+        record.subject = record.subject.or(self.subject.clone());
+        record.username = record.username.or(self.username.clone());
+        record
+    }
+}
+
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Describes a timestamp relative to now, e.g. "in 4 minutes" or "2 hours ago"
+pub fn describe_timestamp(timestamp: i64) -> String {
+    let delta = timestamp - now();
+    let amount = delta.unsigned_abs();
+    let (value, unit) = match amount {
+        0..60 => (amount, "second"),
+        60..3600 => (amount / 60, "minute"),
+        3600..86400 => (amount / 3600, "hour"),
+        _ => (amount / 86400, "day"),
+    };
+    let plural = if value == 1 { "" } else { "s" };
+    if delta >= 0 {
+        format!("in {value} {unit}{plural}")
+    } else {
+        format!("{value} {unit}{plural} ago")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+
+    fn token(claims: &str) -> String {
+        format!(
+            "{}.{}.signature",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"RS256"}"#),
+            URL_SAFE_NO_PAD.encode(claims)
+        )
+    }
+
+    #[test]
+    fn migrates_legacy_bare_tokens() {
+        let access_token = token(
+            r#"{"iss":"https://vibes.test/realms/vibes","sub":"42","preferred_username":"alice","exp":1714600800,"scope":"openid offline_access"}"#,
+        );
+        let (record, is_legacy) = CredentialRecord::parse(&format!(" {access_token}\n")).unwrap();
+        assert!(is_legacy);
+        assert_eq!(record.version, RECORD_VERSION);
+        assert_eq!(record.access_token, access_token);
+        assert_eq!(record.refresh_token, None);
+        assert_eq!(record.access_expires_at, Some(1714600800));
+        assert_eq!(
+            record.issuer.as_deref(),
+            Some("https://vibes.test/realms/vibes")
+        );
+        assert_eq!(record.subject.as_deref(), Some("42"));
+        assert_eq!(record.username.as_deref(), Some("alice"));
+        assert_eq!(record.scopes, ["openid", "offline_access"]);
+        assert!(record.is_offline());
+    }
+
+    #[test]
+    fn migrates_opaque_legacy_tokens() {
+        let (record, is_legacy) = CredentialRecord::parse("opaque-token").unwrap();
+        assert!(is_legacy);
+        assert_eq!(record.access_token, "opaque-token");
+        assert_eq!(record.access_expires_at, None);
+        assert_eq!(record.username, None);
+        assert!(!record.is_access_expired(0));
+    }
+
+    #[test]
+    fn reads_records_back() {
+        let mut record = CredentialRecord::from_access_token(&token(r#"{"exp":1}"#));
+        record.refresh_token = Some("refresh".to_owned());
+        let (parsed, is_legacy) = CredentialRecord::parse(&record.to_secret().unwrap()).unwrap();
+        assert!(!is_legacy);
+        assert_eq!(parsed.access_token, record.access_token);
+        assert_eq!(parsed.refresh_token.as_deref(), Some("refresh"));
+        assert!(parsed.is_access_expired(0));
+        assert!(CredentialRecord::parse("{not json").is_err());
+    }
+}
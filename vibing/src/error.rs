@@ -1,6 +1,7 @@
-use std::io::ErrorKind;
-
-use crate::authentication::response::KeycloakError;
+use crate::{
+    authentication::response::KeycloakError,
+    creds::keyring_store::{self, KeyringFailure},
+};
 
 pub type Error = Box<dyn std::error::Error>;
 
@@ -8,17 +9,73 @@ pub type Result<T> = std::result::Result<T, AppError>;
 
 #[derive(Debug)]
 pub enum AppError {
-    CredentialsNotFound(keyring::Error),
+    /// No credential is stored for the requested account
+    CredentialsNotFound,
+    /// The secure storage is locked and could not be unlocked
+    CredentialStoreLocked(String),
+    /// The user dismissed the prompt granting access to the secure storage
+    CredentialAccessCancelled,
+    CredentialStoreError(String),
+    /// The credential helper request cannot be served, with the reason
+    CredentialHelperError(String),
+    /// A required environment variable is not set
+    MissingEnv(String),
+    ConfigError(String),
     AuthorizationError(Option<reqwest::Error>),
     NetworkError(Option<reqwest::Error>),
     FetchError(reqwest::Error),
     KeycloakError(KeycloakError),
+    TokenUnavailable(String),
+    /// The token shows that the user lacks the role required by the operation
+    MissingRole(String),
+    /// The action needs the user but vibing runs non-interactively
+    InteractionRequired(String),
+    CassetteError(String),
+    /// The `--query` expression cannot be parsed, with the reason
+    InvalidQuery(String, String),
+    /// The `--until` condition cannot be parsed
+    InvalidCondition(String),
+    /// The output template cannot be parsed or rendered
+    TemplateError(String),
+    /// The output cannot be formatted as requested, e.g. as JSON
+    UnsupportedOutput(String),
+    /// The alias is invalid or expands recursively
+    AliasError(String),
+    /// Some steps of the logout failed on the server, with the step and its error
+    LogoutFailed(Vec<(String, AppError)>),
+    /// The logging cannot be set up, e.g. invalid `VIBING_LOG` directives
+    LoggingError(String),
+    /// Neither a command nor an installed plugin
+    UnknownCommand(String),
+    /// The user stopped the command with Ctrl-C
+    Interrupted,
+    /// The plugin exited with a non-zero code, which vibing exits with silently
+    PluginFailed(String, i32),
+    /// No club matches the name
+    ClubNotFound(String),
+    /// Several clubs match the name, with the description of each
+    AmbiguousClub(String, Vec<String>),
+    /// No club has the name, only the described one is named like it
+    InexactClubMatch(String, String),
     Other(Error),
 }
 
 impl From<keyring::Error> for AppError {
     fn from(err: keyring::Error) -> Self {
-        AppError::CredentialsNotFound(err)
+        if let keyring::Error::NoEntry = err {
+            return AppError::CredentialsNotFound;
+        }
+        let message = match &err {
+            keyring::Error::NoStorageAccess(reason) | keyring::Error::PlatformFailure(reason) => {
+                reason.to_string()
+            }
+            other_error => other_error.to_string(),
+        };
+        match keyring_store::classify(&err) {
+            Some(KeyringFailure::Locked) => AppError::CredentialStoreLocked(message),
+            Some(KeyringFailure::Cancelled) => AppError::CredentialAccessCancelled,
+            _ => AppError::CredentialStoreError(message),
+        }
     }
 }
 
@@ -44,6 +101,18 @@ impl From<KeycloakError> for AppError {
     }
 }
 
+impl From<std::io::Error> for AppError {
+    fn from(err: std::io::Error) -> Self {
+        AppError::Other(Box::new(err))
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(err: serde_json::Error) -> Self {
+        AppError::Other(Box::new(err))
+    }
+}
+
 impl From<Error> for AppError {
     fn from(err: Error) -> Self {
         AppError::Other(err)
@@ -55,37 +124,207 @@ pub fn build_generic_error(msg: Option<&str>) -> std::io::Error {
     if let Some(msg) = msg {
         error_msg = msg;
     }
-    std::io::Error::new(ErrorKind::Other, error_msg)
+    std::io::Error::other(error_msg)
 }
 
-pub fn handle(err: AppError) -> () {
-    match err {
-        AppError::AuthorizationError(_) => {
-            println!("You are not authorized to execute this operation")
-        }
-        AppError::NetworkError(_) => {
-            println!("The server is experiencing some issues at the moment.");
-            println!("Please, try again in a few minutes.");
-        }
-        AppError::FetchError(_) => {
-            println!("An unknown error occured while fetching data.");
-            println!("Please, try again.");
-        }
-        AppError::CredentialsNotFound(_) => {
-            println!("Credentials not found in the device's secure storage...");
-            println!("Try to authenticate again using");
-            println!("`vibes auth login`");
-        }
-        AppError::KeycloakError(_) => {
-            println!("An error occured while checking your authentication token.");
-            println!("Please, try again or, if the problem persists, try logging in again using");
-            println!("`vibing auth login`");
-        }
-        AppError::Other(_) => {
-            println!("An unknown error occured while satisfying your request...");
-            println!(
-                "Please, try again. If the problem persists, try to contact the technical support."
-            );
+impl AppError {
+    /// Exit code of vibing when failing with the error, forwarding the one of failed plugins
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AppError::PluginFailed(_, code) => *code,
+            // As shells report processes ended by SIGINT
+            AppError::Interrupted => 130,
+            _ => 1,
         }
     }
+
+    /// What went wrong and how to fix it, as shown to the user
+    pub fn message(&self) -> String {
+        let lines = match self {
+            AppError::AuthorizationError(_) => {
+                vec!["You are not authorized to execute this operation".to_owned()]
+            }
+            AppError::NetworkError(_) => {
+                vec![
+                    "The server is experiencing some issues at the moment.".to_owned(),
+                    "Please, try again in a few minutes.".to_owned(),
+                ]
+            }
+            AppError::FetchError(_) => {
+                vec![
+                    "An unknown error occured while fetching data.".to_owned(),
+                    "Please, try again.".to_owned(),
+                ]
+            }
+            AppError::CredentialsNotFound => {
+                vec![
+                    "Credentials not found in the device's secure storage...".to_owned(),
+                    "Try to authenticate again using".to_owned(),
+                    "`vibing auth login`".to_owned(),
+                ]
+            }
+            AppError::CredentialStoreLocked(reason) => {
+                vec![
+                    format!("The device's secure storage is locked ({reason})."),
+                    "Unlock your keyring (e.g. by logging in to your desktop session) and try again,".to_owned(),
+                    "or use the encrypted file storage by setting `credential_store = \"file\"` in the configuration.".to_owned(),
+                ]
+            }
+            AppError::CredentialAccessCancelled => {
+                vec![
+                    "Access to the device's secure storage was cancelled.".to_owned(),
+                    "Run the command again and allow vibing to access your credentials.".to_owned(),
+                ]
+            }
+            AppError::CredentialHelperError(reason) => vec![reason.to_string()],
+            AppError::CredentialStoreError(reason) => {
+                vec![
+                    "Could not access the credential storage.".to_owned(),
+                    reason.to_string(),
+                    "Run `vibing auth storage` to check which storage is in use.".to_owned(),
+                ]
+            }
+            AppError::MissingEnv(variable) => {
+                vec![
+                    format!("The `{variable}` environment variable is not set."),
+                    "Set it or add it to the `.env` file (see `.env.sample`).".to_owned(),
+                ]
+            }
+            AppError::ConfigError(reason) => {
+                vec![reason.to_string()]
+            }
+            AppError::KeycloakError(_) => {
+                vec![
+                    "An error occured while checking your authentication token.".to_owned(),
+                    "Please, try again or, if the problem persists, try logging in again using"
+                        .to_owned(),
+                    "`vibing auth login`".to_owned(),
+                ]
+            }
+            AppError::TokenUnavailable(reason) => {
+                vec![
+                    "Could not obtain an access token.".to_owned(),
+                    reason.to_string(),
+                ]
+            }
+            AppError::MissingRole(role) => {
+                vec![
+                    format!(
+                        "This operation requires role {role}, which your account does not have."
+                    ),
+                    "Ask an administrator to grant it, or switch to another account using"
+                        .to_owned(),
+                    "`vibing auth switch <user>`".to_owned(),
+                ]
+            }
+            AppError::InteractionRequired(action) => {
+                vec![
+                    format!("{action} needs your interaction, which is disabled in non-interactive mode."),
+                    "Run it again from a terminal without `--non-interactive` or `VIBING_NON_INTERACTIVE`.".to_owned(),
+                ]
+            }
+            AppError::InvalidQuery(query, reason) => {
+                vec![
+                    format!("`{query}` is not a valid JSONPath expression: {reason}"),
+                    "Queries look like `$.content[*].clubName`.".to_owned(),
+                ]
+            }
+            AppError::InvalidCondition(condition) => {
+                vec![
+                    format!("`{condition}` is not a valid condition."),
+                    "Conditions compare a field with a value, e.g. `deleted == true` or `totalCount >= 10`.".to_owned(),
+                ]
+            }
+            AppError::TemplateError(reason) => {
+                vec![
+                    "Could not render the output template.".to_owned(),
+                    reason.to_string(),
+                ]
+            }
+            AppError::UnsupportedOutput(output) => {
+                vec![
+                    format!("{output} cannot be formatted."),
+                    "Run it again without `--output json`, `--fields`, `--query` or `--template`."
+                        .to_owned(),
+                ]
+            }
+            AppError::AliasError(reason) => {
+                vec![reason.to_string()]
+            }
+            AppError::LogoutFailed(failures) => {
+                let mut lines = vec!["Could not log out completely:".to_owned()];
+                lines.extend(failures.iter().map(|(step, err)| {
+                    let reason = match err {
+                        AppError::KeycloakError(keycloak_error) => {
+                            keycloak_error.error_description.clone()
+                        }
+                        AppError::FetchError(_) | AppError::NetworkError(_) => {
+                            "the server could not be reached".to_owned()
+                        }
+                        other_error => other_error.message().replace('\n', " "),
+                    };
+                    format!("  - {step}: {reason}")
+                }));
+                lines
+            }
+            AppError::LoggingError(reason) => {
+                vec!["Could not set up the logs.".to_owned(), reason.to_string()]
+            }
+            AppError::UnknownCommand(name) => {
+                vec![
+                    format!("`{name}` is neither a vibing command nor an installed plugin."),
+                    "Run `vibing --help` for the commands, or `vibing plugins list` for the plugins."
+                        .to_owned(),
+                ]
+            }
+            AppError::Interrupted => vec!["Interrupted.".to_owned()],
+            AppError::PluginFailed(name, code) => {
+                vec![format!("The `{name}` plugin exited with code {code}.")]
+            }
+            AppError::ClubNotFound(name) => {
+                vec![
+                    format!("No club matches `{name}`."),
+                    "Run `vibing club list` to see the available clubs.".to_owned(),
+                ]
+            }
+            AppError::AmbiguousClub(name, candidates) => {
+                let mut lines = vec![format!("Several clubs match `{name}`:")];
+                lines.extend(
+                    candidates
+                        .iter()
+                        .map(|candidate| format!("  - {candidate}")),
+                );
+                lines.push("Pass the ID of the club instead.".to_owned());
+                lines
+            }
+            AppError::InexactClubMatch(name, candidate) => {
+                vec![
+                    format!("No club is named `{name}`, the closest is {candidate}."),
+                    "Pass its ID or its exact name instead.".to_owned(),
+                ]
+            }
+            AppError::CassetteError(reason) => {
+                vec![
+                    "Could not replay the request from the cassette.".to_owned(),
+                    reason.to_string(),
+                ]
+            }
+            AppError::Other(err) => {
+                vec![
+                    "An unknown error occured while satisfying your request:".to_owned(),
+                    err.to_string(),
+                    "Please, try again. If the problem persists, try to contact the technical support.".to_owned(),
+                ]
+            }
+        };
+        lines.join("\n")
+    }
+}
+
+pub fn handle(err: AppError) {
+    // Ctrl-C needs no explanation, and failed plugins report their own errors
+    if let AppError::Interrupted | AppError::PluginFailed(..) = err {
+        return;
+    }
+    eprintln!("{}", err.message());
 }
@@ -1,3 +1,6 @@
+pub mod cache;
 pub mod club_requests;
+pub mod lookup;
+pub mod opening;
 pub mod request;
 pub mod response;
@@ -0,0 +1,117 @@
+use clap_complete::CompletionCandidate;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use crate::{config, error::Result, features::club::response::ClubResponse};
+
+const CACHE_FILE: &str = "clubs.json";
+
+/// A club as remembered from previous responses
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedClub {
+    pub club_name: String,
+    pub address: String,
+}
+
+/// Local index of the clubs seen in previous responses, used for completion and lookups by
+/// name without listing every club from the backend
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ClubCache {
+    pub clubs: BTreeMap<u32, CachedClub>,
+}
+
+impl ClubCache {
+    /// Reads the cache, which is empty when missing or unreadable
+    pub fn load() -> ClubCache {
+        let Ok(path) = cache_path() else {
+            return ClubCache::default();
+        };
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<ClubCache>(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = cache_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+/// Adds the clubs to the cache, skipping the deleted ones. Failures are ignored as the cache is
+/// only a convenience.
+pub fn remember(clubs: &[ClubResponse]) {
+    let mut cache = ClubCache::load();
+    for club in clubs {
+        if club.deleted {
+            cache.clubs.remove(&club.id);
+            continue;
+        }
+        cache.clubs.insert(
+            club.id,
+            CachedClub {
+                club_name: club.club_name.clone(),
+                address: club.address.clone(),
+            },
+        );
+    }
+    let _ = cache.save();
+}
+
+/// Replaces the cache with the clubs, e.g. after listing every club from the backend
+pub fn rebuild(clubs: &[ClubResponse]) -> Result<()> {
+    let cache = ClubCache {
+        clubs: clubs
+            .iter()
+            .filter(|club| !club.deleted)
+            .map(|club| {
+                (
+                    club.id,
+                    CachedClub {
+                        club_name: club.club_name.clone(),
+                        address: club.address.clone(),
+                    },
+                )
+            })
+            .collect(),
+    };
+    cache.save()
+}
+
+pub fn forget(club_id: u32) {
+    let mut cache = ClubCache::load();
+    if cache.clubs.remove(&club_id).is_some() {
+        let _ = cache.save();
+    }
+}
+
+/// Completion candidates for club ID arguments, described by the club name
+pub fn club_id_candidates() -> Vec<CompletionCandidate> {
+    ClubCache::load()
+        .clubs
+        .into_iter()
+        .map(|(club_id, club)| {
+            CompletionCandidate::new(club_id.to_string()).help(Some(club.club_name.into()))
+        })
+        .collect()
+}
+
+/// Completion candidates for arguments taking either the ID or the name of a club
+pub fn club_candidates() -> Vec<CompletionCandidate> {
+    let clubs = ClubCache::load().clubs;
+    let names = clubs.iter().map(|(club_id, club)| {
+        CompletionCandidate::new(club.club_name.clone()).help(Some(format!("ID {club_id}").into()))
+    });
+    let mut candidates: Vec<CompletionCandidate> = names.collect();
+    candidates.extend(club_id_candidates());
+    candidates
+}
+
+fn cache_path() -> Result<PathBuf> {
+    Ok(config::cache_dir()?.join(CACHE_FILE))
+}
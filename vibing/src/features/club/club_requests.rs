@@ -1,11 +1,59 @@
 use reqwest::Method;
 
-use crate::{error::Result, features::club::response::ClubResponse, requests::fetch_backend};
-
-pub async fn get_club_info(club_id: u32, verbose: bool) -> Result<()> {
-    let formatted_path = format!("/api/v1/club/{club_id}").to_owned();
-    let club_response =
-        fetch_backend::<(), ClubResponse>(formatted_path, Method::GET, None, verbose).await?;
-    println!("{club_response:#?}");
-    Ok(())
+use crate::{
+    client::VibingClient,
+    error::Result,
+    features::club::{request::ClubRequest, response::ClubResponse},
+    requests::{PageOptions, PageResponse},
+};
+
+const CLUB_PATH: &str = "/api/v1/club";
+
+impl VibingClient {
+    pub async fn get_club(&self, club_id: u32) -> Result<ClubResponse> {
+        let req = self
+            .request(Method::GET, &format!("{CLUB_PATH}/{club_id}"))
+            .await?;
+        self.send::<ClubResponse>(req).await
+    }
+
+    pub async fn list_clubs(
+        &self,
+        page_options: &PageOptions,
+    ) -> Result<PageResponse<ClubResponse>> {
+        let req = self
+            .request(Method::GET, CLUB_PATH)
+            .await?
+            .query(page_options);
+        self.send::<PageResponse<ClubResponse>>(req).await
+    }
+
+    pub async fn create_club(&self, club: &ClubRequest) -> Result<ClubResponse> {
+        let req = self.request(Method::POST, CLUB_PATH).await?.json(club);
+        self.send::<ClubResponse>(req).await
+    }
+
+    pub async fn update_club(&self, club_id: u32, club: &ClubRequest) -> Result<ClubResponse> {
+        let req = self
+            .request(Method::PUT, &format!("{CLUB_PATH}/{club_id}"))
+            .await?
+            .json(club);
+        self.send::<ClubResponse>(req).await
+    }
+
+    /// Soft-deletes the club
+    pub async fn delete_club(&self, club_id: u32) -> Result<()> {
+        let req = self
+            .request(Method::DELETE, &format!("{CLUB_PATH}/{club_id}"))
+            .await?;
+        self.send::<()>(req).await
+    }
+
+    /// Undoes the soft-deletion of the club, through `PATCH /api/v1/club/{id}/restore`
+    pub async fn restore_club(&self, club_id: u32) -> Result<ClubResponse> {
+        let req = self
+            .request(Method::PATCH, &format!("{CLUB_PATH}/{club_id}/restore"))
+            .await?;
+        self.send::<ClubResponse>(req).await
+    }
 }
@@ -0,0 +1,197 @@
+use tracing::warn;
+
+use crate::{
+    client::VibingClient,
+    error::Result,
+    features::club::cache::{self, CachedClub, ClubCache},
+    requests::PageOptions,
+};
+
+/// Number of clubs fetched per request when indexing every club
+const INDEX_PAGE_SIZE: u32 = 100;
+
+/// Pages fetched at most when indexing, should the backend keep returning full pages
+const MAX_INDEX_PAGES: u32 = 100;
+
+/// How closely a club name matches the searched one, from the closest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    Exact,
+    Prefix,
+    Substring,
+    /// The searched characters appear in order, e.g. `bhain` for `Berghain`
+    Subsequence,
+    /// The names differ by a few characters, e.g. `Amnsia` for `Amnesia`
+    Typo,
+}
+
+/// The clubs matching a name the closest, all matching the same way
+#[derive(Debug, Clone)]
+pub struct ClubMatches {
+    pub kind: MatchKind,
+    pub clubs: Vec<(u32, CachedClub)>,
+}
+
+/// Finds the clubs named like `name` in the local index, fetching every club from the backend
+/// unless the index has an exact match: the index may miss a club named closer, or list one
+/// which was renamed since.
+///
+/// Only the closest matches are returned, and only exact (case-insensitive) ones when `exact`.
+pub async fn search(client: &VibingClient, name: &str, exact: bool) -> Result<Option<ClubMatches>> {
+    if let Some(matches) = find_matches(&ClubCache::load(), name, exact)
+        && matches.kind == MatchKind::Exact
+    {
+        return Ok(Some(matches));
+    }
+    index_clubs(client).await?;
+    Ok(find_matches(&ClubCache::load(), name, exact))
+}
+
+pub fn find_matches(cache: &ClubCache, name: &str, exact: bool) -> Option<ClubMatches> {
+    let matches: Vec<(MatchKind, u32, &CachedClub)> = cache
+        .clubs
+        .iter()
+        .filter_map(|(club_id, club)| {
+            let kind = match_kind(club.club_name.as_str(), name)?;
+            (!exact || kind == MatchKind::Exact).then_some((kind, *club_id, club))
+        })
+        .collect();
+    let closest_kind = matches.iter().map(|(kind, _, _)| *kind).min()?;
+    let clubs = matches
+        .into_iter()
+        .filter(|(kind, _, _)| *kind == closest_kind)
+        .map(|(_, club_id, club)| (club_id, club.clone()))
+        .collect();
+    Some(ClubMatches {
+        kind: closest_kind,
+        clubs,
+    })
+}
+
+pub fn match_kind(club_name: &str, name: &str) -> Option<MatchKind> {
+    let club_name = club_name.trim().to_lowercase();
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return None;
+    }
+    if club_name == name {
+        return Some(MatchKind::Exact);
+    }
+    if club_name.starts_with(name.as_str()) {
+        return Some(MatchKind::Prefix);
+    }
+    if club_name.contains(name.as_str()) {
+        return Some(MatchKind::Substring);
+    }
+    let mut club_name_chars = club_name.chars();
+    if name
+        .chars()
+        .all(|character| club_name_chars.any(|club_name_char| club_name_char == character))
+    {
+        return Some(MatchKind::Subsequence);
+    }
+    let max_distance = (name.chars().count() / 4).max(1);
+    (edit_distance(club_name.as_str(), name.as_str()) <= max_distance).then_some(MatchKind::Typo)
+}
+
+/// Lists every club from the backend into the local index.
+///
+/// Stops once the total count is reached, or without one at an empty or short page, and in any
+/// case after `MAX_INDEX_PAGES` pages.
+async fn index_clubs(client: &VibingClient) -> Result<()> {
+    let mut clubs = Vec::new();
+    for page in 0..MAX_INDEX_PAGES {
+        let page_options = PageOptions::new(Some(page), Some(INDEX_PAGE_SIZE), None, None, None);
+        let clubs_page = client.list_clubs(&page_options).await?;
+        let page_len = clubs_page.content.len();
+        clubs.extend(clubs_page.content);
+        let is_last_page = match clubs_page.total_count {
+            Some(total_count) => clubs.len() as u64 >= total_count,
+            None => page_len < INDEX_PAGE_SIZE as usize,
+        };
+        if page_len == 0 || is_last_page {
+            return cache::rebuild(&clubs);
+        }
+    }
+    warn!(
+        "Only the first {} clubs are indexed, the backend kept returning pages.",
+        clubs.len()
+    );
+    cache::rebuild(&clubs)
+}
+
+/// Levenshtein distance between the two strings
+fn edit_distance(first: &str, second: &str) -> usize {
+    let second: Vec<char> = second.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=second.len()).collect();
+    for (first_index, first_char) in first.chars().enumerate() {
+        let mut row = vec![first_index + 1];
+        for (second_index, second_char) in second.iter().enumerate() {
+            let substitution = previous_row[second_index] + usize::from(first_char != *second_char);
+            let insertion = row[second_index] + 1;
+            let deletion = previous_row[second_index + 1] + 1;
+            row.push(substitution.min(insertion).min(deletion));
+        }
+        previous_row = row;
+    }
+    previous_row[second.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn ranks_the_way_names_match() {
+        assert_eq!(match_kind("Amnesia", " amnesia "), Some(MatchKind::Exact));
+        assert_eq!(match_kind("Amnesia", "amn"), Some(MatchKind::Prefix));
+        assert_eq!(match_kind("Amnesia", "nesi"), Some(MatchKind::Substring));
+        assert_eq!(
+            match_kind("Berghain", "bhain"),
+            Some(MatchKind::Subsequence)
+        );
+        assert_eq!(match_kind("Amnesia", "Amnesja"), Some(MatchKind::Typo));
+        assert_eq!(match_kind("Amnesia", "Pacha"), None);
+        assert_eq!(match_kind("Amnesia", "  "), None);
+    }
+
+    #[test]
+    fn allows_more_typos_in_longer_names() {
+        assert_eq!(match_kind("Pacha", "Pachq"), Some(MatchKind::Typo));
+        assert_eq!(match_kind("Pacha", "Pxchq"), None);
+        assert_eq!(
+            match_kind("Space Ibiza", "Spase Ibisa"),
+            Some(MatchKind::Typo)
+        );
+    }
+
+    #[test]
+    fn keeps_only_the_closest_matches() {
+        let club = |club_name: &str| CachedClub {
+            club_name: club_name.to_owned(),
+            address: String::new(),
+        };
+        let cache = ClubCache {
+            clubs: BTreeMap::from([
+                (1, club("Pacha")),
+                (2, club("Pacha Ibiza")),
+                (3, club("Pacha Barcelona")),
+            ]),
+        };
+        let matches = find_matches(&cache, "pacha ", false).unwrap();
+        assert_eq!(matches.kind, MatchKind::Exact);
+        assert_eq!(matches.clubs.len(), 1);
+        let matches = find_matches(&cache, "pacha i", false).unwrap();
+        assert_eq!(matches.kind, MatchKind::Prefix);
+        assert_eq!(matches.clubs[0].0, 2);
+        assert!(find_matches(&cache, "pacha i", true).is_none());
+    }
+
+    #[test]
+    fn measures_edit_distances() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("amnesia", "amnesia"), 0);
+    }
+}
@@ -0,0 +1,74 @@
+use chrono::{NaiveTime, TimeDelta};
+use std::fmt;
+
+use crate::features::club::response::ClubResponse;
+
+/// Whether a club is open at a given time of the day
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OpeningStatus {
+    Open { closes_in: TimeDelta },
+    Closed { opens_in: TimeDelta },
+}
+
+impl fmt::Display for OpeningStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpeningStatus::Open { closes_in } => {
+                write!(f, "Open, closes in {}", describe_delta(*closes_in))
+            }
+            OpeningStatus::Closed { opens_in } => {
+                write!(f, "Closed, opens in {}", describe_delta(*opens_in))
+            }
+        }
+    }
+}
+
+/// Parses the `HH:MM:SS` (or `HH:MM`) times of the backend
+pub fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(time.trim(), "%H:%M"))
+        .ok()
+}
+
+/// `None` when the opening hours cannot be parsed.
+///
+/// Clubs closing at or before their opening time close the next day, e.g. from 23:00 to 06:00.
+pub fn opening_status(club: &ClubResponse, now: NaiveTime) -> Option<OpeningStatus> {
+    let opening_time = parse_time(club.opening_time.as_str())?;
+    let closing_time = parse_time(club.closing_time.as_str())?;
+    let is_open = if closing_time > opening_time {
+        now >= opening_time && now < closing_time
+    } else {
+        now >= opening_time || now < closing_time
+    };
+    Some(if is_open {
+        OpeningStatus::Open {
+            closes_in: time_until(now, closing_time),
+        }
+    } else {
+        OpeningStatus::Closed {
+            opens_in: time_until(now, opening_time),
+        }
+    })
+}
+
+fn time_until(now: NaiveTime, time: NaiveTime) -> TimeDelta {
+    let delta = time - now;
+    if delta <= TimeDelta::zero() {
+        delta + TimeDelta::days(1)
+    } else {
+        delta
+    }
+}
+
+fn describe_delta(delta: TimeDelta) -> String {
+    let hours = delta.num_hours();
+    let minutes = delta.num_minutes() % 60;
+    if hours > 0 {
+        format!("{hours}h {minutes:02}m")
+    } else if minutes > 0 {
+        format!("{minutes}m")
+    } else {
+        format!("{}s", delta.num_seconds())
+    }
+}
@@ -1 +1,14 @@
+use serde::{Deserialize, Serialize};
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClubRequest {
+    pub club_name: String,
+    pub vat_code: String,
+    pub address: String,
+    pub category: String,
+    pub opening_time: String,
+    pub closing_time: String,
+    pub phone_number: String,
+    pub image: Option<String>,
+}
@@ -0,0 +1,71 @@
+use std::{
+    io::{self, IsTerminal, Write},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::error::{AppError, Result};
+
+/// Environment variable enabling the non-interactive mode, e.g. on unattended kiosks
+pub const NON_INTERACTIVE_ENV: &str = "VIBING_NON_INTERACTIVE";
+
+static NON_INTERACTIVE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_non_interactive(non_interactive: bool) {
+    NON_INTERACTIVE.store(non_interactive, Ordering::Relaxed);
+}
+
+/// Whether vibing may wait for the user, false when `--non-interactive` or
+/// `VIBING_NON_INTERACTIVE` is set
+pub fn is_interactive() -> bool {
+    let env_non_interactive = std::env::var(NON_INTERACTIVE_ENV)
+        .is_ok_and(|value| !matches!(value.trim(), "" | "0" | "false"));
+    !NON_INTERACTIVE.load(Ordering::Relaxed) && !env_non_interactive
+}
+
+/// Fails instead of prompting the user when running non-interactively
+pub fn require_interactive(action: &str) -> Result<()> {
+    if is_interactive() {
+        return Ok(());
+    }
+    Err(AppError::InteractionRequired(action.to_owned()))
+}
+
+/// Whether the user can be asked a question, i.e. vibing is interactive and reads a terminal
+pub fn can_prompt() -> bool {
+    is_interactive() && io::stdin().is_terminal()
+}
+
+/// Asks the user a yes/no question, `no` being the default
+pub fn confirm(question: &str) -> Result<bool> {
+    require_interactive("Confirming")?;
+    print!("{question} [y/N]: ");
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer)? == 0 {
+        return Err(AppError::InteractionRequired("Confirming".to_owned()));
+    }
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Asks the user to pick one of the options, returning its index
+pub fn choose(question: &str, options: &[String]) -> Result<usize> {
+    require_interactive("Choosing")?;
+    println!("{question}");
+    for (index, option) in options.iter().enumerate() {
+        println!("  {}) {option}", index + 1);
+    }
+    loop {
+        print!("Choose 1-{}: ", options.len());
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer)? == 0 {
+            return Err(AppError::InteractionRequired("Choosing".to_owned()));
+        }
+        if let Ok(choice) = answer.trim().parse::<usize>()
+            && (1..=options.len()).contains(&choice)
+        {
+            return Ok(choice - 1);
+        }
+        println!("Please, enter a number between 1 and {}.", options.len());
+    }
+}
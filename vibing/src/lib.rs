@@ -1,8 +1,19 @@
 pub mod authentication;
+pub mod browser;
 pub mod cli;
+pub mod client;
+pub mod config;
 pub mod creds;
 pub mod env;
 pub mod error;
 pub mod features;
+pub mod interactive;
+pub mod logging;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod output;
+pub mod plugins;
 pub mod requests;
+pub mod tui;
 pub mod utils;
+pub mod watch;
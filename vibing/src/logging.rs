@@ -0,0 +1,178 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::{self, IsTerminal},
+    path::PathBuf,
+    sync::OnceLock,
+};
+use tracing::level_filters::LevelFilter;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{
+    EnvFilter, Layer, Registry, fmt, layer::SubscriberExt, reload, util::SubscriberInitExt,
+};
+
+use crate::{
+    config,
+    error::{AppError, Result},
+    requests::trace::HTTP_LOG_TARGET,
+};
+
+/// Environment variable replacing the log filter with `tracing` directives, e.g.
+/// `vibing=debug,reqwest=trace`
+pub const LOG_ENV: &str = "VIBING_LOG";
+
+const LOG_DIR: &str = "logs";
+const LOG_FILE_PREFIX: &str = "vibing";
+const LOG_FILE_SUFFIX: &str = "log";
+/// Days of logs kept in the log directory
+const MAX_LOG_FILES: usize = 7;
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Swaps the layers of the installed subscriber, which can only be installed once per process
+static LOG_LAYER: OnceLock<reload::Handle<BoxedLayer, Registry>> = OnceLock::new();
+
+#[derive(ValueEnum, Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Off,
+    Error,
+    #[default]
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    /// `-v` shows the info logs, `-vv` the debug ones and `-vvv` everything
+    pub fn from_verbosity(verbosity: u8) -> Option<LogLevel> {
+        match verbosity {
+            0 => None,
+            1 => Some(LogLevel::Info),
+            2 => Some(LogLevel::Debug),
+            _ => Some(LogLevel::Trace),
+        }
+    }
+
+    fn filter(self) -> LevelFilter {
+        match self {
+            LogLevel::Off => LevelFilter::OFF,
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+#[derive(ValueEnum, Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line, for log aggregation
+    Json,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LogOptions {
+    pub level: LogLevel,
+    pub format: LogFormat,
+    /// Also write the logs to daily rotated files in `<state dir>/vibing/logs`
+    pub file: bool,
+    /// Write the logs to stderr, off while a full-screen UI is drawn
+    pub stderr: bool,
+    /// Show the HTTP traces whatever the level, for `--trace-http` and `--print-curl`
+    pub http: bool,
+}
+
+impl LogOptions {
+    /// Warnings to stderr, until the command line and the configuration are read
+    pub fn startup() -> LogOptions {
+        LogOptions {
+            level: LogLevel::default(),
+            format: LogFormat::default(),
+            file: false,
+            stderr: true,
+            http: false,
+        }
+    }
+}
+
+/// Sets up the logs for the whole process, until the next call (e.g. the next command of the
+/// shell).
+///
+/// Only the logs of vibing are shown at the chosen level, those of its dependencies never go
+/// below `warn`. `VIBING_LOG` replaces both.
+pub fn init(options: LogOptions) -> Result<()> {
+    let layer = build_layer(options)?;
+    if let Some(handle) = LOG_LAYER.get() {
+        return handle
+            .reload(layer)
+            .map_err(|err| AppError::LoggingError(err.to_string()));
+    }
+    let (layer, handle) = reload::Layer::new(layer);
+    tracing_subscriber::registry()
+        .with(layer)
+        .try_init()
+        .map_err(|err| AppError::LoggingError(err.to_string()))?;
+    let _ = LOG_LAYER.set(handle);
+    Ok(())
+}
+
+/// `$XDG_STATE_HOME/vibing/logs` or the platform equivalent
+pub fn log_dir() -> Result<PathBuf> {
+    Ok(config::state_dir()?.join(LOG_DIR))
+}
+
+fn build_layer(options: LogOptions) -> Result<BoxedLayer> {
+    // Disabled outputs are left out rather than passed as `None`, whose level hint would turn
+    // the other outputs off once boxed
+    let mut outputs: Vec<BoxedLayer> = Vec::new();
+    if options.stderr {
+        outputs.push(match options.format {
+            LogFormat::Text => fmt::layer()
+                .with_writer(io::stderr)
+                .with_ansi(io::stderr().is_terminal())
+                .boxed(),
+            LogFormat::Json => fmt::layer().json().with_writer(io::stderr).boxed(),
+        });
+    }
+    if options.file {
+        let log_dir = log_dir()?;
+        fs::create_dir_all(&log_dir)?;
+        let appender = RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_FILE_PREFIX)
+            .filename_suffix(LOG_FILE_SUFFIX)
+            .max_log_files(MAX_LOG_FILES)
+            .build(log_dir)
+            .map_err(|err| AppError::LoggingError(err.to_string()))?;
+        outputs.push(match options.format {
+            LogFormat::Text => fmt::layer().with_writer(appender).with_ansi(false).boxed(),
+            LogFormat::Json => fmt::layer().json().with_writer(appender).boxed(),
+        });
+    }
+    Ok(filter(options)?.and_then(outputs).boxed())
+}
+
+fn filter(options: LogOptions) -> Result<EnvFilter> {
+    let directives = match std::env::var(LOG_ENV) {
+        Ok(directives) if !directives.trim().is_empty() => directives,
+        _ => {
+            let level = options.level.filter();
+            let mut directives = format!("{},vibing={level}", level.min(LevelFilter::WARN));
+            if options.http {
+                directives.push_str(format!(",{HTTP_LOG_TARGET}=debug").as_str());
+            }
+            directives
+        }
+    };
+    EnvFilter::builder()
+        .parse(directives.as_str())
+        .map_err(|err| AppError::LoggingError(format!("Invalid `{LOG_ENV}` filter: {err}")))
+}
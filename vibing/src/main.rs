@@ -1,16 +1,27 @@
-use vibing::{cli::matcher, env, error::handle};
+use clap::CommandFactory;
+use clap_complete::CompleteEnv;
+use tracing::debug;
+use vibing::{
+    cli::{args::VibingCliParser, matcher},
+    env,
+    error::handle,
+};
 
 #[tokio::main]
 async fn main() {
-    println!("Vibing ~");
+    // Answers the completion requests of the scripts printed by `vibing completions`
+    CompleteEnv::with_factory(VibingCliParser::command)
+        .var(matcher::COMPLETE_ENV)
+        .complete();
+    eprintln!("Vibing ~");
     env::load();
-    // FIXME: Still panicks ig user cancels the credentials read operation
     let result = matcher::handle_cli_args().await;
     if let Err(result_err) = &result {
-        println!("Error thrown");
-        println!("{result_err:#?}");
+        debug!("Error thrown: {result_err:?}");
     }
     if let Err(err) = result {
+        let exit_code = err.exit_code();
         handle(err);
+        std::process::exit(exit_code);
     }
 }
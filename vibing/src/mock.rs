@@ -0,0 +1,152 @@
+pub mod club;
+pub mod keycloak;
+
+use axum::Router;
+use serde_json::json;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    net::SocketAddr,
+    sync::{Arc, Mutex, MutexGuard},
+};
+use tokio::net::TcpListener;
+
+use crate::{
+    authentication::jwt::encode_unsigned, creds::record::now, error::Result,
+    features::club::response::ClubResponse,
+};
+
+pub const MOCK_ISSUER: &str = "http://vibing-mock/realms/vibes";
+pub const MOCK_SUBJECT: &str = "00000000-0000-0000-0000-000000000001";
+pub const MOCK_USERNAME: &str = "mock-user";
+
+/// Seconds an access token issued by the mock stays valid
+pub const ACCESS_TOKEN_LIFESPAN: i32 = 300;
+/// Seconds a device code issued by the mock stays valid
+pub const DEVICE_CODE_LIFESPAN: i32 = 600;
+/// Seconds a refresh token issued by the mock stays valid
+pub const REFRESH_TOKEN_LIFESPAN: i32 = 1800;
+/// Seconds an offline session of the mock stays idle before expiring
+pub const OFFLINE_SESSION_IDLE: i64 = 30 * 86400;
+
+#[derive(Debug, Default)]
+pub struct MockData {
+    /// Device codes mapped to the number of times they have been polled
+    pub device_codes: HashMap<String, u32>,
+    /// Issued access tokens mapped to whether they are still active
+    pub access_tokens: HashMap<String, bool>,
+    /// Issued refresh tokens mapped to whether they are still active
+    pub refresh_tokens: HashMap<String, bool>,
+    /// Device codes and refresh tokens requesting or belonging to an offline session
+    pub offline_sessions: HashSet<String>,
+    pub clubs: BTreeMap<u32, ClubResponse>,
+    pub issued_tokens: u64,
+    /// Realm roles granted in the issued access tokens
+    pub roles: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MockState {
+    data: Arc<Mutex<MockData>>,
+}
+
+impl MockState {
+    pub fn new() -> MockState {
+        MockState::default()
+    }
+
+    /// Creates a state whose club API is populated with a few sample clubs
+    pub fn seeded() -> MockState {
+        let state = MockState::new();
+        club::seed(&mut state.lock());
+        state
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, MockData> {
+        self.data
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Issues an access token which the mock will accept as bearer authorization.
+    ///
+    /// Tokens are unsigned JWTs, so that the claims can be decoded as with Keycloak's.
+    pub fn issue_token(&self, scope: &str) -> String {
+        let mut data = self.lock();
+        data.issued_tokens += 1;
+        let issued_at = now();
+        let token = encode_unsigned(&json!({
+            "jti": format!("mock-access-token-{}", data.issued_tokens),
+            "iss": MOCK_ISSUER,
+            "sub": MOCK_SUBJECT,
+            "preferred_username": MOCK_USERNAME,
+            "iat": issued_at,
+            "exp": issued_at + i64::from(ACCESS_TOKEN_LIFESPAN),
+            "scope": scope,
+            "realm_access": { "roles": data.roles },
+        }));
+        data.access_tokens.insert(token.clone(), true);
+        token
+    }
+
+    pub fn issue_refresh_token(&self) -> String {
+        let mut data = self.lock();
+        data.issued_tokens += 1;
+        let token = format!("mock-refresh-token-{}", data.issued_tokens);
+        data.refresh_tokens.insert(token.clone(), true);
+        token
+    }
+
+    /// Offline tokens carry their idle expiry in their claims, like Keycloak's
+    pub fn issue_offline_token(&self) -> String {
+        let mut data = self.lock();
+        data.issued_tokens += 1;
+        let issued_at = now();
+        let token = encode_unsigned(&json!({
+            "jti": format!("mock-offline-token-{}", data.issued_tokens),
+            "iss": MOCK_ISSUER,
+            "sub": MOCK_SUBJECT,
+            "typ": "Offline",
+            "iat": issued_at,
+            "exp": issued_at + OFFLINE_SESSION_IDLE,
+        }));
+        data.refresh_tokens.insert(token.clone(), true);
+        data.offline_sessions.insert(token.clone());
+        token
+    }
+
+    pub fn is_token_active(&self, token: &str) -> bool {
+        self.lock()
+            .access_tokens
+            .get(token)
+            .copied()
+            .unwrap_or(false)
+    }
+}
+
+/// Builds the router emulating both Keycloak (under `/realms/{realm}`) and the backend API,
+/// so that integration tests and demos can run fully locally
+pub fn router(state: MockState) -> Router {
+    Router::new()
+        .merge(keycloak::router())
+        .merge(club::router())
+        .with_state(state)
+}
+
+/// Binds `addr` and serves the mock in a background task, returning the bound address.
+///
+/// Use port `0` to let the OS choose a free port.
+pub async fn spawn(addr: SocketAddr, state: MockState) -> Result<SocketAddr> {
+    let listener = TcpListener::bind(addr).await?;
+    let local_addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, router(state)).await;
+    });
+    Ok(local_addr)
+}
+
+/// Serves the mock on `addr` until the process is stopped
+pub async fn serve(addr: SocketAddr, state: MockState) -> Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    axum::serve(listener, router(state)).await?;
+    Ok(())
+}
@@ -0,0 +1,277 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode, header::AUTHORIZATION},
+    response::{IntoResponse, Response},
+    routing::{get, patch},
+};
+use serde_json::Value;
+use std::cmp::Ordering;
+
+use crate::{
+    authentication::{jwt::decode_claims, roles::CLUB_ADMIN_ROLE},
+    features::club::{request::ClubRequest, response::ClubResponse},
+    mock::{MOCK_USERNAME, MockData, MockState},
+    requests::{PageDirection, PageOptions, PageResponse},
+};
+
+pub fn router() -> Router<MockState> {
+    Router::new()
+        .route("/api/v1/club", get(list_clubs).post(create_club))
+        .route(
+            "/api/v1/club/{club_id}",
+            get(get_club).put(update_club).delete(delete_club),
+        )
+        .route("/api/v1/club/{club_id}/restore", patch(restore_club))
+}
+
+/// Populates the club API with a few sample clubs
+pub fn seed(data: &mut MockData) {
+    let samples = [
+        (
+            "Amnesia",
+            "Disco",
+            "Carretera Ibiza a San Antonio, Ibiza",
+            "23:00:00",
+            "06:00:00",
+        ),
+        (
+            "Berghain",
+            "Techno",
+            "Am Wriezener Bahnhof, Berlin",
+            "23:59:00",
+            "12:00:00",
+        ),
+        (
+            "Fabric",
+            "Electronic",
+            "77A Charterhouse St, London",
+            "23:00:00",
+            "07:00:00",
+        ),
+        (
+            "Pacha",
+            "House",
+            "Av. 8 d'Agost, Ibiza",
+            "23:30:00",
+            "06:30:00",
+        ),
+        (
+            "Space",
+            "Electronic",
+            "Platja d'en Bossa, Ibiza",
+            "22:00:00",
+            "06:00:00",
+        ),
+        (
+            "Cocoricò",
+            "Disco",
+            "Via Chieti 44, Riccione",
+            "23:00:00",
+            "05:00:00",
+        ),
+    ];
+    for (index, (club_name, category, address, opening_time, closing_time)) in
+        samples.into_iter().enumerate()
+    {
+        insert_club(
+            data,
+            ClubRequest {
+                club_name: club_name.to_owned(),
+                vat_code: format!("IT{:011}", index + 1),
+                address: address.to_owned(),
+                category: category.to_owned(),
+                opening_time: opening_time.to_owned(),
+                closing_time: closing_time.to_owned(),
+                phone_number: format!("+39 0541 00000{}", index + 1),
+                image: None,
+            },
+        );
+    }
+}
+
+fn insert_club(data: &mut MockData, club: ClubRequest) -> ClubResponse {
+    let id = data.clubs.keys().next_back().copied().unwrap_or(0) + 1;
+    let club_response = ClubResponse {
+        created_by: MOCK_USERNAME.to_owned(),
+        modified_by: MOCK_USERNAME.to_owned(),
+        deleted: false,
+        id,
+        club_name: club.club_name,
+        vat_code: club.vat_code,
+        address: club.address,
+        category: club.category,
+        opening_time: club.opening_time,
+        closing_time: club.closing_time,
+        phone_number: club.phone_number,
+        image: club.image,
+    };
+    data.clubs.insert(id, club_response.clone());
+    club_response
+}
+
+async fn list_clubs(
+    State(state): State<MockState>,
+    headers: HeaderMap,
+    Query(page_options): Query<PageOptions>,
+) -> Response {
+    if !is_authorized(&state, &headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    let mut clubs: Vec<ClubResponse> = state
+        .lock()
+        .clubs
+        .values()
+        .filter(|club| !club.deleted)
+        .cloned()
+        .collect();
+    if let Some(sort) = &page_options.sort {
+        clubs.sort_by(|first, second| compare_field(first, second, sort));
+    }
+    if page_options.direction == Some(PageDirection::Desc) {
+        clubs.reverse();
+    }
+    let Some(offset) = (page_options.page as usize).checked_mul(page_options.size as usize) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    let total_count = clubs.len() as u64;
+    let content = clubs
+        .into_iter()
+        .skip(offset)
+        .take(page_options.size as usize)
+        .collect();
+    Json(PageResponse {
+        content,
+        page: page_options.page,
+        size: page_options.size,
+        total_count: page_options.show_total_count.then_some(total_count),
+    })
+    .into_response()
+}
+
+async fn get_club(
+    State(state): State<MockState>,
+    headers: HeaderMap,
+    Path(club_id): Path<u32>,
+) -> Response {
+    if !is_authorized(&state, &headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    match state.lock().clubs.get(&club_id) {
+        Some(club) => Json(club.clone()).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn create_club(
+    State(state): State<MockState>,
+    headers: HeaderMap,
+    Json(club): Json<ClubRequest>,
+) -> Response {
+    if !is_authorized(&state, &headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    let club_response = insert_club(&mut state.lock(), club);
+    (StatusCode::CREATED, Json(club_response)).into_response()
+}
+
+async fn update_club(
+    State(state): State<MockState>,
+    headers: HeaderMap,
+    Path(club_id): Path<u32>,
+    Json(club): Json<ClubRequest>,
+) -> Response {
+    if !is_authorized(&state, &headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    if !has_role(&headers, CLUB_ADMIN_ROLE) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    let mut data = state.lock();
+    let Some(club_response) = data.clubs.get_mut(&club_id) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    club_response.club_name = club.club_name;
+    club_response.vat_code = club.vat_code;
+    club_response.address = club.address;
+    club_response.category = club.category;
+    club_response.opening_time = club.opening_time;
+    club_response.closing_time = club.closing_time;
+    club_response.phone_number = club.phone_number;
+    club_response.image = club.image;
+    club_response.modified_by = MOCK_USERNAME.to_owned();
+    Json(club_response.clone()).into_response()
+}
+
+/// Clubs are soft-deleted, as the backend does
+async fn delete_club(
+    State(state): State<MockState>,
+    headers: HeaderMap,
+    Path(club_id): Path<u32>,
+) -> Response {
+    if !is_authorized(&state, &headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    if !has_role(&headers, CLUB_ADMIN_ROLE) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    match state.lock().clubs.get_mut(&club_id) {
+        Some(club) => {
+            club.deleted = true;
+            club.modified_by = MOCK_USERNAME.to_owned();
+            StatusCode::NO_CONTENT.into_response()
+        }
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn restore_club(
+    State(state): State<MockState>,
+    headers: HeaderMap,
+    Path(club_id): Path<u32>,
+) -> Response {
+    if !is_authorized(&state, &headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    if !has_role(&headers, CLUB_ADMIN_ROLE) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    match state.lock().clubs.get_mut(&club_id) {
+        Some(club) => {
+            club.deleted = false;
+            club.modified_by = MOCK_USERNAME.to_owned();
+            Json(club.clone()).into_response()
+        }
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+fn is_authorized(state: &MockState, headers: &HeaderMap) -> bool {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|authorization| authorization.to_str().ok())
+        .and_then(|authorization| authorization.strip_prefix("Bearer "))
+        .is_some_and(|token| state.is_token_active(token))
+}
+
+fn has_role(headers: &HeaderMap, role: &str) -> bool {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|authorization| authorization.to_str().ok())
+        .and_then(|authorization| authorization.strip_prefix("Bearer "))
+        .and_then(decode_claims)
+        .is_some_and(|claims| claims.roles().iter().any(|granted| granted == role))
+}
+
+/// Compares two clubs by the field with the given (camelCase) name
+fn compare_field(first: &ClubResponse, second: &ClubResponse, field: &str) -> Ordering {
+    let first = serde_json::to_value(first).unwrap_or_default();
+    let second = serde_json::to_value(second).unwrap_or_default();
+    match (&first[field], &second[field]) {
+        (Value::Number(first), Value::Number(second)) => first
+            .as_f64()
+            .partial_cmp(&second.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (first, second) => first.to_string().cmp(&second.to_string()),
+    }
+}
@@ -0,0 +1,201 @@
+use axum::{
+    Form, Json, Router,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode, header::HOST},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use serde::Deserialize;
+
+use crate::{
+    authentication::{
+        keycloak_auth::OFFLINE_SCOPE,
+        request::{KeycloakAuth, KeycloakJwtIntrospect, KeycloakLogout, KeycloakRevokeToken},
+        response::{
+            DeviceCodeAuth, KeycloakError, KeycloakJwtActiveStatus,
+            KeycloakSuccessfulAuthentication,
+        },
+    },
+    mock::{ACCESS_TOKEN_LIFESPAN, DEVICE_CODE_LIFESPAN, MockState, REFRESH_TOKEN_LIFESPAN},
+};
+
+const DEVICE_CODE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+#[derive(Deserialize, Debug)]
+pub struct TokenForm {
+    pub grant_type: String,
+    pub device_code: Option<String>,
+    pub refresh_token: Option<String>,
+}
+
+pub fn router() -> Router<MockState> {
+    Router::new()
+        .route(
+            "/realms/{realm}/protocol/openid-connect/auth/device",
+            post(device_code),
+        )
+        .route("/realms/{realm}/device", get(verify_device))
+        .route("/realms/{realm}/protocol/openid-connect/token", post(token))
+        .route(
+            "/realms/{realm}/protocol/openid-connect/token/introspect",
+            post(introspect),
+        )
+        .route(
+            "/realms/{realm}/protocol/openid-connect/revoke",
+            post(revoke),
+        )
+        .route(
+            "/realms/{realm}/protocol/openid-connect/logout",
+            post(end_session),
+        )
+}
+
+async fn device_code(
+    State(state): State<MockState>,
+    Path(realm): Path<String>,
+    headers: HeaderMap,
+    Form(form): Form<KeycloakAuth>,
+) -> Json<DeviceCodeAuth> {
+    let mut data = state.lock();
+    let user_code = format!("MOCK-{:04}", data.device_codes.len() + 1);
+    let device_code = format!("mock-device-code-{}", data.device_codes.len() + 1);
+    data.device_codes.insert(device_code.clone(), 0);
+    let is_offline = form
+        .scope
+        .is_some_and(|scope| scope.split_whitespace().any(|scope| scope == OFFLINE_SCOPE));
+    if is_offline {
+        data.offline_sessions.insert(device_code.clone());
+    }
+    let host = headers
+        .get(HOST)
+        .and_then(|host| host.to_str().ok())
+        .unwrap_or("localhost");
+    let verification_uri = format!("http://{host}/realms/{realm}/device");
+    Json(DeviceCodeAuth {
+        device_code,
+        verification_uri_complete: format!("{verification_uri}?user_code={user_code}"),
+        verification_uri,
+        user_code,
+        expires_in: DEVICE_CODE_LIFESPAN,
+        interval: 5,
+    })
+}
+
+async fn verify_device() -> &'static str {
+    "Device approved, you can go back to your terminal."
+}
+
+async fn token(State(state): State<MockState>, Form(form): Form<TokenForm>) -> Response {
+    match form.grant_type.as_str() {
+        DEVICE_CODE_GRANT => device_code_grant(state, form),
+        "refresh_token" => refresh_token_grant(state, form),
+        _ => keycloak_error("unsupported_grant_type", "Unsupported grant type"),
+    }
+}
+
+/// Device codes are approved automatically after being polled once
+fn device_code_grant(state: MockState, form: TokenForm) -> Response {
+    let device_code = form.device_code.unwrap_or_default();
+    let polls = {
+        let mut data = state.lock();
+        let Some(polls) = data.device_codes.get_mut(&device_code) else {
+            return keycloak_error("invalid_grant", "Device code not valid");
+        };
+        *polls += 1;
+        *polls
+    };
+    if polls == 1 {
+        return keycloak_error(
+            "authorization_pending",
+            "The authorization request is still pending",
+        );
+    }
+    let is_offline = {
+        let mut data = state.lock();
+        data.device_codes.remove(&device_code);
+        data.offline_sessions.remove(&device_code)
+    };
+    successful_authentication(&state, is_offline)
+}
+
+/// Refresh tokens are rotated on every use
+fn refresh_token_grant(state: MockState, form: TokenForm) -> Response {
+    let refresh_token = form.refresh_token.unwrap_or_default();
+    let (was_active, is_offline) = {
+        let mut data = state.lock();
+        let was_active = data
+            .refresh_tokens
+            .insert(refresh_token.clone(), false)
+            .unwrap_or(false);
+        (was_active, data.offline_sessions.remove(&refresh_token))
+    };
+    if !was_active {
+        return keycloak_error("invalid_grant", "Invalid refresh token");
+    }
+    successful_authentication(&state, is_offline)
+}
+
+/// Offline sessions get a refresh token without session lifespan, reported as 0 like Keycloak
+fn successful_authentication(state: &MockState, is_offline: bool) -> Response {
+    let scope = if is_offline {
+        format!("openid profile email {OFFLINE_SCOPE}")
+    } else {
+        "openid profile email".to_owned()
+    };
+    let (refresh_token, refresh_expires_in) = if is_offline {
+        (state.issue_offline_token(), 0)
+    } else {
+        (state.issue_refresh_token(), REFRESH_TOKEN_LIFESPAN)
+    };
+    Json(KeycloakSuccessfulAuthentication {
+        access_token: state.issue_token(scope.as_str()),
+        expires_in: ACCESS_TOKEN_LIFESPAN,
+        refresh_token: Some(refresh_token),
+        refresh_expires_in: Some(refresh_expires_in),
+        token_type: "Bearer".to_owned(),
+        session_state: "mock-session".to_owned(),
+        scope,
+    })
+    .into_response()
+}
+
+async fn introspect(
+    State(state): State<MockState>,
+    Form(form): Form<KeycloakJwtIntrospect>,
+) -> Json<KeycloakJwtActiveStatus> {
+    Json(KeycloakJwtActiveStatus {
+        active: state.is_token_active(&form.token),
+    })
+}
+
+async fn revoke(State(state): State<MockState>, Form(form): Form<KeycloakRevokeToken>) {
+    let mut data = state.lock();
+    if let Some(active) = data.access_tokens.get_mut(&form.token) {
+        *active = false;
+    }
+    if let Some(active) = data.refresh_tokens.get_mut(&form.token) {
+        *active = false;
+    }
+}
+
+async fn end_session(State(state): State<MockState>, Form(form): Form<KeycloakLogout>) -> Response {
+    let mut data = state.lock();
+    match data.refresh_tokens.get_mut(&form.refresh_token) {
+        Some(active) if *active => {
+            *active = false;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        _ => keycloak_error("invalid_grant", "Session not active"),
+    }
+}
+
+fn keycloak_error(error: &str, error_description: &str) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(KeycloakError {
+            error: error.to_owned(),
+            error_description: error_description.to_owned(),
+        }),
+    )
+        .into_response()
+}
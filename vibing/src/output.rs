@@ -0,0 +1,236 @@
+pub mod template;
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use serde_json_path::JsonPath;
+use std::sync::RwLock;
+
+use crate::{
+    error::{AppError, Result},
+    output::template::Template,
+};
+
+static OUTPUT_OPTIONS: RwLock<OutputOptions> = RwLock::new(OutputOptions {
+    format: OutputFormat::Pretty,
+    fields: Vec::new(),
+    query: None,
+    template: None,
+});
+
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq)]
+pub enum OutputFormat {
+    /// Human-readable output, pretty-printed JSON once filtered
+    #[default]
+    Pretty,
+    /// One compact JSON document per line, for scripts
+    Json,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OutputOptions {
+    pub format: OutputFormat,
+    /// Fields to keep in the printed objects, e.g. `club_name` or `clubName`
+    pub fields: Vec<String>,
+    /// JSONPath expression selecting what to print, e.g. `$.content[*].clubName`
+    pub query: Option<String>,
+    /// Handlebars template each printed value is rendered through
+    pub template: Option<String>,
+}
+
+impl OutputOptions {
+    /// Whether the response is printed as is, in its human-readable form
+    fn is_human_readable(&self) -> bool {
+        self.format == OutputFormat::Pretty
+            && self.fields.is_empty()
+            && self.query.is_none()
+            && self.template.is_none()
+    }
+}
+
+/// Sets the output options for the whole process, until the next call (e.g. the next command
+/// of the shell)
+pub fn init(options: OutputOptions) {
+    *OUTPUT_OPTIONS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = options;
+}
+
+pub fn options() -> OutputOptions {
+    OUTPUT_OPTIONS
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+/// Prints a response: `render` prints it for humans, unless JSON output, fields, a query or a
+/// template are requested, in which case they are applied to its serialized form.
+///
+/// Templates render every item of arrays on its own.
+pub fn print<T>(value: &T, render: impl FnOnce(&T)) -> Result<()>
+where
+    T: Serialize,
+{
+    let options = options();
+    if options.is_human_readable() {
+        render(value);
+        return Ok(());
+    }
+    let template = options.template.as_deref().map(Template::new).transpose()?;
+    for result in select(serde_json::to_value(value)?, &options)? {
+        match &template {
+            Some(template) => {
+                for line in render_template(template, result)? {
+                    println!("{line}");
+                }
+            }
+            None => println!("{}", format_value(&result, options.format)?),
+        }
+    }
+    Ok(())
+}
+
+/// Fails when JSON output, fields, a query or a template are requested for an output they
+/// cannot apply to, e.g. a manual page
+pub fn require_human_readable(output: &str) -> Result<()> {
+    if options().is_human_readable() {
+        return Ok(());
+    }
+    Err(AppError::UnsupportedOutput(output.to_owned()))
+}
+
+/// Applies the query, then keeps the requested fields of every result
+pub fn select(value: Value, options: &OutputOptions) -> Result<Vec<Value>> {
+    let results = match &options.query {
+        Some(query) => apply_query(&value, query.as_str())?,
+        None => vec![value],
+    };
+    Ok(results
+        .into_iter()
+        .map(|result| select_fields(result, &options.fields))
+        .collect())
+}
+
+/// Renders the value through the template, every item on its own for arrays
+pub fn render_template(template: &Template, value: Value) -> Result<Vec<String>> {
+    match value {
+        Value::Array(items) => items.iter().map(|item| template.render(item)).collect(),
+        value => Ok(vec![template.render(&value)?]),
+    }
+}
+
+/// Every node the JSONPath expression selects
+pub fn apply_query(value: &Value, query: &str) -> Result<Vec<Value>> {
+    let path = JsonPath::parse(query)
+        .map_err(|err| AppError::InvalidQuery(query.to_owned(), err.to_string()))?;
+    Ok(path.query(value).all().into_iter().cloned().collect())
+}
+
+/// Keeps the requested fields of the objects, looking through arrays and wrappers such as pages
+/// which have none of them
+pub fn select_fields(value: Value, fields: &[String]) -> Value {
+    if fields.is_empty() {
+        return value;
+    }
+    match value {
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| select_fields(item, fields))
+                .collect(),
+        ),
+        Value::Object(object) => {
+            let has_field = object
+                .keys()
+                .any(|key| fields.iter().any(|field| is_same_field(key, field)));
+            if has_field {
+                let selected: Map<String, Value> = object
+                    .into_iter()
+                    .filter(|(key, _)| fields.iter().any(|field| is_same_field(key, field)))
+                    .collect();
+                return Value::Object(selected);
+            }
+            Value::Object(
+                object
+                    .into_iter()
+                    .map(|(key, value)| (key, select_fields(value, fields)))
+                    .collect(),
+            )
+        }
+        value => value,
+    }
+}
+
+/// Field names match regardless of their case, so `club_name` selects `clubName`
+pub fn is_same_field(key: &str, field: &str) -> bool {
+    let normalize = |name: &str| name.replace(['_', '-'], "").to_lowercase();
+    normalize(key) == normalize(field)
+}
+
+/// Strings are printed raw in the pretty format, so that they can be used directly in scripts
+pub fn format_value(value: &Value, format: OutputFormat) -> Result<String> {
+    Ok(match (format, value) {
+        (OutputFormat::Pretty, Value::String(string)) => string.clone(),
+        (OutputFormat::Pretty, value) => serde_json::to_string_pretty(value)?,
+        (OutputFormat::Json, value) => serde_json::to_string(value)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fields(fields: &[&str]) -> Vec<String> {
+        fields.iter().map(|field| field.to_string()).collect()
+    }
+
+    #[test]
+    fn keeps_the_requested_fields_in_any_case() {
+        let club = json!({"id": 1, "clubName": "Pacha", "address": "Ibiza"});
+        assert_eq!(
+            select_fields(club, &fields(&["club_name", "ID"])),
+            json!({"id": 1, "clubName": "Pacha"})
+        );
+    }
+
+    #[test]
+    fn looks_through_pages_and_arrays() {
+        let page = json!({
+            "content": [{"id": 1, "clubName": "Pacha"}, {"id": 2, "clubName": "Amnesia"}],
+            "totalCount": 2,
+        });
+        assert_eq!(
+            select_fields(page, &fields(&["clubName"])),
+            json!({
+                "content": [{"clubName": "Pacha"}, {"clubName": "Amnesia"}],
+                "totalCount": 2,
+            })
+        );
+    }
+
+    #[test]
+    fn leaves_the_value_without_fields() {
+        let club = json!({"id": 1, "clubName": "Pacha"});
+        assert_eq!(select_fields(club.clone(), &[]), club);
+        assert_eq!(
+            select_fields(json!("Pacha"), &fields(&["id"])),
+            json!("Pacha")
+        );
+    }
+
+    #[test]
+    fn selects_the_fields_of_the_query_results() {
+        let options = OutputOptions {
+            query: Some("$.content[?@.id > 1]".to_owned()),
+            fields: fields(&["club_name"]),
+            ..OutputOptions::default()
+        };
+        let page =
+            json!({"content": [{"id": 1, "clubName": "Pacha"}, {"id": 2, "clubName": "Amnesia"}]});
+        assert_eq!(
+            select(page, &options).unwrap(),
+            vec![json!({"clubName": "Amnesia"})]
+        );
+    }
+}
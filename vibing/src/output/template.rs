@@ -0,0 +1,292 @@
+use chrono::{
+    DateTime, Local, NaiveDate,
+    format::{DelayedFormat, Item, StrftimeItems},
+};
+use handlebars::{
+    Context, Handlebars, Helper, HelperResult, Output, RenderContext, RenderError,
+    RenderErrorReason, no_escape,
+};
+use serde_json::{Map, Value};
+use std::fmt::Write;
+
+use crate::{
+    error::{AppError, Result},
+    features::club::opening::parse_time,
+};
+
+const TEMPLATE_NAME: &str = "output";
+const DEFAULT_DATE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+const DEFAULT_TIME_FORMAT: &str = "%H:%M:%S";
+
+/// Renders responses through a Handlebars template.
+///
+/// Besides the built-in `if`, `unless`, `each`, `eq`, `and`... helpers, templates can use:
+/// - `{{date value "%d/%m %H:%M"}}` for timestamps (in seconds), dates and times, printed like
+///   `2024-05-01 23:00:00`, `2024-05-01` and `23:00:00` without a format
+/// - `{{pad value 20}}` and `{{pad_start value 5}}` to align columns
+/// - `{{default value "none"}}` for missing or empty values
+pub struct Template {
+    handlebars: Handlebars<'static>,
+}
+
+impl Template {
+    pub fn new(template: &str) -> Result<Template> {
+        let mut handlebars = Handlebars::new();
+        handlebars.register_escape_fn(no_escape);
+        handlebars.register_helper("date", Box::new(date_helper));
+        handlebars.register_helper("pad", Box::new(pad_helper));
+        handlebars.register_helper("pad_start", Box::new(pad_start_helper));
+        handlebars.register_helper("default", Box::new(default_helper));
+        handlebars
+            .register_template_string(TEMPLATE_NAME, template)
+            .map_err(|err| AppError::TemplateError(err.to_string()))?;
+        Ok(Template { handlebars })
+    }
+
+    /// Fields can be referred to both by their serialized name and in snake case, e.g.
+    /// `{{clubName}}` or `{{club_name}}`
+    pub fn render(&self, value: &Value) -> Result<String> {
+        self.handlebars
+            .render(TEMPLATE_NAME, &with_snake_case_keys(value.clone()))
+            .map_err(|err| AppError::TemplateError(err.to_string()))
+    }
+}
+
+fn with_snake_case_keys(value: Value) -> Value {
+    match value {
+        Value::Array(items) => Value::Array(items.into_iter().map(with_snake_case_keys).collect()),
+        Value::Object(object) => {
+            let mut aliased = Map::new();
+            for (key, value) in object {
+                let value = with_snake_case_keys(value);
+                let snake_case_key = to_snake_case(key.as_str());
+                if snake_case_key != key {
+                    aliased.insert(snake_case_key, value.clone());
+                }
+                aliased.insert(key, value);
+            }
+            Value::Object(aliased)
+        }
+        value => value,
+    }
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut snake_case = String::new();
+    for character in name.chars() {
+        if character.is_uppercase() {
+            snake_case.push('_');
+            snake_case.extend(character.to_lowercase());
+        } else {
+            snake_case.push(character);
+        }
+    }
+    snake_case
+}
+
+/// The value as printed in a template, strings without their quotes
+fn display(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(string) => string.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn param<'a>(
+    helper: &'a Helper,
+    name: &'static str,
+    index: usize,
+) -> std::result::Result<&'a Value, RenderError> {
+    helper
+        .param(index)
+        .map(|param| param.value())
+        .ok_or_else(|| RenderErrorReason::ParamNotFoundForIndex(name, index).into())
+}
+
+fn width_param(helper: &Helper, name: &'static str) -> std::result::Result<usize, RenderError> {
+    param(helper, name, 1)?
+        .as_u64()
+        .map(|width| width as usize)
+        .ok_or_else(|| RenderErrorReason::InvalidParamType("width").into())
+}
+
+fn date_helper(
+    helper: &Helper,
+    _: &Handlebars,
+    _: &Context,
+    _: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let value = param(helper, "date", 0)?;
+    let format = helper.param(1).and_then(|param| param.value().as_str());
+    if let Some(format) = format
+        && StrftimeItems::new(format).any(|item| item == Item::Error)
+    {
+        return Err(
+            RenderErrorReason::Other(format!("`{format}` is not a valid date format")).into(),
+        );
+    }
+    let formatted = match value {
+        Value::Number(number) => number
+            .as_i64()
+            .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+            .map(|date_time| {
+                let format = format.unwrap_or(DEFAULT_DATE_TIME_FORMAT);
+                (date_time.with_timezone(&Local).format(format), format)
+            }),
+        Value::String(string) => DateTime::parse_from_rfc3339(string)
+            .ok()
+            .map(|date_time| {
+                let format = format.unwrap_or(DEFAULT_DATE_TIME_FORMAT);
+                (date_time.with_timezone(&Local).format(format), format)
+            })
+            .or_else(|| {
+                parse_time(string).map(|time| {
+                    let format = format.unwrap_or(DEFAULT_TIME_FORMAT);
+                    (time.format(format), format)
+                })
+            })
+            .or_else(|| {
+                NaiveDate::parse_from_str(string, "%Y-%m-%d")
+                    .ok()
+                    .map(|date| {
+                        let format = format.unwrap_or(DEFAULT_DATE_FORMAT);
+                        (date.format(format), format)
+                    })
+            }),
+        _ => None,
+    };
+    match formatted {
+        Some((formatted, format)) => out.write(write_date(formatted, format)?.as_str())?,
+        None => out.write(display(value).as_str())?,
+    }
+    Ok(())
+}
+
+/// Formats through `write!`, which fails where `to_string` panics when the format needs what
+/// the value lacks, e.g. `%H` for a date
+fn write_date(
+    formatted: DelayedFormat<StrftimeItems>,
+    format: &str,
+) -> std::result::Result<String, RenderError> {
+    let mut date = String::new();
+    write!(date, "{formatted}").map_err(|_| {
+        RenderErrorReason::Other(format!("`{format}` does not apply to this value"))
+    })?;
+    Ok(date)
+}
+
+fn pad_helper(
+    helper: &Helper,
+    _: &Handlebars,
+    _: &Context,
+    _: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let value = display(param(helper, "pad", 0)?);
+    let width = width_param(helper, "pad")?;
+    out.write(format!("{value:<width$}").as_str())?;
+    Ok(())
+}
+
+fn pad_start_helper(
+    helper: &Helper,
+    _: &Handlebars,
+    _: &Context,
+    _: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let value = display(param(helper, "pad_start", 0)?);
+    let width = width_param(helper, "pad_start")?;
+    out.write(format!("{value:>width$}").as_str())?;
+    Ok(())
+}
+
+fn default_helper(
+    helper: &Helper,
+    _: &Handlebars,
+    _: &Context,
+    _: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let value = display(param(helper, "default", 0)?);
+    if value.is_empty() {
+        out.write(display(param(helper, "default", 1)?).as_str())?;
+    } else {
+        out.write(value.as_str())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render(template: &str, value: Value) -> Result<String> {
+        Template::new(template)?.render(&value)
+    }
+
+    #[test]
+    fn formats_times_and_dates() {
+        let club =
+            json!({"openingTime": "23:00:00", "closingTime": "06:30", "openedOn": "2024-05-01"});
+        assert_eq!(
+            render(
+                "{{date opening_time \"%H:%M\"}}-{{date closingTime}} {{date opened_on \"%d/%m\"}}",
+                club
+            )
+            .unwrap(),
+            "23:00-06:30:00 01/05"
+        );
+    }
+
+    #[test]
+    fn formats_timestamps_in_local_time() {
+        let expected = DateTime::from_timestamp(1714600800, 0)
+            .unwrap()
+            .with_timezone(&Local)
+            .format(DEFAULT_DATE_TIME_FORMAT)
+            .to_string();
+        assert_eq!(
+            render("{{date modified}}", json!({"modified": 1714600800})).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn prints_other_values_as_is() {
+        assert_eq!(
+            render("{{date name \"%H\"}}", json!({"name": "Pacha"})).unwrap(),
+            "Pacha"
+        );
+    }
+
+    #[test]
+    fn rejects_formats_that_do_not_apply() {
+        for (template, value) in [
+            ("{{date time \"%Q\"}}", json!({"time": "23:00"})),
+            ("{{date day \"%H:%M\"}}", json!({"day": "2024-05-01"})),
+        ] {
+            assert!(
+                matches!(render(template, value), Err(AppError::TemplateError(_))),
+                "{template}"
+            );
+        }
+    }
+
+    #[test]
+    fn pads_and_defaults_values() {
+        let club = json!({"clubName": "Pacha", "capacity": 30, "address": ""});
+        assert_eq!(
+            render(
+                "[{{pad club_name 7}}|{{pad_start capacity 4}}|{{default address \"none\"}}]",
+                club
+            )
+            .unwrap(),
+            "[Pacha  |  30|none]"
+        );
+    }
+}
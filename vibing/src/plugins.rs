@@ -0,0 +1,107 @@
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    env::consts::EXE_SUFFIX,
+    fs,
+    path::{Path, PathBuf},
+};
+use tokio::process::Command;
+
+use crate::{
+    authentication::token_provider::TOKEN_ENV,
+    client::VibingClient,
+    creds::accounts::{self, PROFILE_ENV},
+    error::{AppError, Result},
+};
+use tracing::info;
+
+/// Plugins are executables on `PATH` named `vibing-<command>`
+pub const PLUGIN_PREFIX: &str = "vibing-";
+/// Environment variable through which plugins receive the backend URL
+pub const BACKEND_URL_ENV: &str = "VIBING_BACKEND_URL";
+
+/// An installed plugin, run as `vibing <name>`
+#[derive(Serialize, Debug, Clone)]
+pub struct Plugin {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// The plugins found on `PATH`, by name. Like the shell, the first directory wins when several
+/// provide the same plugin.
+pub fn discover() -> BTreeMap<String, Plugin> {
+    let mut plugins = BTreeMap::new();
+    let Some(path) = std::env::var_os("PATH") else {
+        return plugins;
+    };
+    for dir in std::env::split_paths(&path) {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let Some(name) = file_name
+                .strip_prefix(PLUGIN_PREFIX)
+                .and_then(|name| name.strip_suffix(EXE_SUFFIX))
+                .filter(|name| !name.is_empty())
+            else {
+                continue;
+            };
+            if plugins.contains_key(name) || !is_executable(&entry.path()) {
+                continue;
+            }
+            plugins.insert(
+                name.to_owned(),
+                Plugin {
+                    name: name.to_owned(),
+                    path: entry.path(),
+                },
+            );
+        }
+    }
+    plugins
+}
+
+pub fn find(name: &str) -> Option<Plugin> {
+    discover().remove(name)
+}
+
+/// Runs the plugin with the arguments, passing it the active profile, the backend URL and a
+/// fresh access token (when logged in) through `VIBING_PROFILE`, `VIBING_BACKEND_URL` and
+/// `VIBING_TOKEN`.
+///
+/// Returns the exit code of the plugin.
+pub async fn run(plugin: &Plugin, args: &[String], client: &VibingClient) -> Result<i32> {
+    let mut command = Command::new(&plugin.path);
+    command
+        .args(args)
+        .env(PROFILE_ENV, accounts::current_profile()?)
+        .env(BACKEND_URL_ENV, client.base_url());
+    match client.access_token().await {
+        Ok(token) => {
+            command.env(TOKEN_ENV, token);
+        }
+        // Plugins may not need the backend, let them report the missing login themselves
+        Err(AppError::CredentialsNotFound) => {
+            info!("Not logged in, running the plugin without a token");
+            command.env_remove(TOKEN_ENV);
+        }
+        Err(err) => return Err(err),
+    }
+    info!("Running {}", plugin.path.display());
+    let status = command.status().await?;
+    // Plugins killed by a signal have no exit code
+    Ok(status.code().unwrap_or(1))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|metadata| metadata.is_file())
+}
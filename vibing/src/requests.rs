@@ -1,14 +1,17 @@
+pub mod cassette;
+pub mod trace;
+
 use crate::{
-    creds::get_cred_use_defaults,
-    env,
+    client::VibingClient,
     error::{AppError, Result, build_generic_error},
-    utils::verbose_print,
 };
 use reqwest::{
-    Method, RequestBuilder,
+    Client, Method, Request, RequestBuilder, StatusCode,
     header::{HeaderMap, HeaderValue},
 };
-use serde::{Serialize, de::DeserializeOwned};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::time::Instant;
+use tracing::{debug, trace};
 
 pub struct FetchOptions<T>
 where
@@ -45,8 +48,31 @@ where
     }
 }
 
-#[derive(Serialize, Debug)]
-#[serde(rename_all(serialize = "camelCase"))]
+/// A fully read HTTP response
+#[derive(Debug)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap<HeaderValue>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Deserializes the response body, treating an empty body as `null`
+    pub fn json<U>(&self) -> Result<U>
+    where
+        U: DeserializeOwned,
+    {
+        let body: &[u8] = if self.body.is_empty() {
+            b"null"
+        } else {
+            &self.body
+        };
+        Ok(serde_json::from_slice::<U>(body)?)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase", default)]
 pub struct PageOptions {
     pub page: u32,
     pub size: u32,
@@ -73,40 +99,46 @@ impl PageOptions {
     }
 }
 
-#[derive(Serialize, Debug)]
+impl Default for PageOptions {
+    fn default() -> Self {
+        PageOptions::new(None, None, None, None, None)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 pub enum PageDirection {
     Desc,
     Asc,
 }
 
-pub async fn fetch_backend<T, U>(
-    path: String,
-    method: Method,
-    data: Option<T>,
-    verbose: bool,
-) -> Result<U>
+/// A page of results as returned by the backend's list endpoints
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PageResponse<T> {
+    pub content: Vec<T>,
+    pub page: u32,
+    pub size: u32,
+    pub total_count: Option<u64>,
+}
+
+pub async fn fetch_backend<T, U>(path: String, method: Method, data: Option<T>) -> Result<U>
 where
     T: Serialize,
     U: DeserializeOwned,
 {
-    let mut fetch_options = FetchOptions::new(
-        env::get_env("BACKEND_URL"),
-        path,
-        method,
-        None,
-        Some(get_cred_use_defaults()?),
-        data,
-    );
-    let res = fetch::<T, U>(&mut fetch_options, verbose).await?;
-    Ok(res)
+    let client = VibingClient::builder().build()?;
+    let mut req = client.request(method, &path).await?;
+    if let Some(req_body) = &data {
+        req = req.json(req_body);
+    }
+    client.send::<U>(req).await
 }
 
-pub async fn fetch<T, U>(fetch_options: &mut FetchOptions<T>, verbose: bool) -> Result<U>
+pub async fn fetch<T, U>(fetch_options: &mut FetchOptions<T>) -> Result<U>
 where
     T: Serialize,
     U: DeserializeOwned,
 {
-    verbose_print(verbose, "Building request...");
     let full_url = format!("{}{}", fetch_options.host.trim(), fetch_options.path.trim());
     let client = reqwest::Client::new();
     let mut req = client.request(fetch_options.method.clone(), full_url);
@@ -114,28 +146,66 @@ where
         req = req.bearer_auth(auth_token);
     }
     if let Some(req_body) = &fetch_options.data {
-        req = set_req_body(&req_body, &mut fetch_options.headers, req)?;
+        req = set_req_body(req_body, &mut fetch_options.headers, req)?;
     }
     if let Some(req_headers) = fetch_options.headers.clone() {
         req = req.headers(req_headers);
     }
     let req = req.build()?;
-    verbose_print(verbose, "Fetching data...");
-    let res = client.execute(req).await?;
-    println!("{}", res.status());
-    verbose_print(verbose, "Done.\nDeserializing response...");
-    if res.status().is_success() {
-        let json_res = res.json::<U>().await?;
-        verbose_print(verbose, "Done.");
-        return Ok(json_res);
+    let res = execute(&client, req).await?;
+    into_result::<U>(res)
+}
+
+/// Deserializes a successful response or maps the failed one to an error
+pub fn into_result<U>(res: HttpResponse) -> Result<U>
+where
+    U: DeserializeOwned,
+{
+    if res.status.is_success() {
+        return res.json::<U>();
     }
     // TODO: Handle validation errors as well
-    if res.status().is_client_error() && res.status().as_str().eq("401") {
+    if res.status == StatusCode::UNAUTHORIZED || res.status == StatusCode::FORBIDDEN {
         return Err(AppError::AuthorizationError(None));
     }
     Err(AppError::NetworkError(None))
 }
 
+/// Sends the request and reads the whole response body.
+///
+/// Every HTTP call goes through here so that wire tracing and cassettes apply to all of them.
+pub async fn execute(client: &Client, req: Request) -> Result<HttpResponse> {
+    trace::trace_request(&req);
+    let (method, url) = (req.method().clone(), trace::redact_url(req.url()));
+    trace!(%method, %url, "Sending request");
+    let started_at = Instant::now();
+    if let Some(res) = cassette::replay(&req)? {
+        trace::trace_response(res.status, &res.headers, &res.body, started_at.elapsed());
+        debug!(%method, %url, status = res.status.as_u16(), "Replayed response");
+        return Ok(res);
+    }
+    let recorded_req = cassette::to_recorded_request(&req);
+    let res = client.execute(req).await?;
+    let status = res.status();
+    let headers = res.headers().clone();
+    let body = res.bytes().await?.to_vec();
+    trace::trace_response(status, &headers, &body, started_at.elapsed());
+    debug!(
+        %method,
+        %url,
+        status = status.as_u16(),
+        elapsed_ms = started_at.elapsed().as_millis() as u64,
+        "Received response"
+    );
+    let res = HttpResponse {
+        status,
+        headers,
+        body,
+    };
+    cassette::record(recorded_req, &res)?;
+    Ok(res)
+}
+
 /// Sets the request body based on the passed `Content-type` header.
 ///
 /// Defaults to `application/json`
@@ -152,7 +222,7 @@ where
         header_map.insert(
             "Content-type",
             HeaderValue::from_str("application/json")
-                .map_err(|_| return AppError::Other(Box::new(build_generic_error(None))))?,
+                .map_err(|_| AppError::Other(Box::new(build_generic_error(None))))?,
         );
         let _ = headers.insert(header_map);
     }
@@ -161,12 +231,14 @@ where
         Some(req_content_type) => {
             let req_content_type_str = req_content_type
                 .to_str()
-                .map_err(|_| return AppError::Other(Box::new(build_generic_error(None))))?;
+                .map_err(|_| AppError::Other(Box::new(build_generic_error(None))))?;
             if req_content_type_str.contains("json") {
                 req = req.json(data);
                 return Ok(req);
             }
-            if req_content_type_str.contains("form-data") {
+            if req_content_type_str.contains("form-data")
+                || req_content_type_str.contains("x-www-form-urlencoded")
+            {
                 req = req.form(data);
                 return Ok(req);
             }
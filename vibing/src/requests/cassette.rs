@@ -0,0 +1,327 @@
+use reqwest::{
+    Request, StatusCode,
+    header::{CONTENT_LENGTH, CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::{
+    authentication::jwt::strip_signature,
+    error::{AppError, Result},
+    requests::{HttpResponse, trace},
+};
+
+/// The cassette currently in use by the process, if any.
+///
+/// Being process-wide, integration tests using cassettes should not run in parallel.
+static CASSETTE: Mutex<Option<Cassette>> = Mutex::new(None);
+
+const REDACTED: &str = "***";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CassetteMode {
+    /// Forward requests to the server and save every request/response pair
+    Record,
+    /// Serve responses from the saved pairs without touching the network
+    Replay,
+}
+
+#[derive(Debug)]
+pub struct Cassette {
+    pub mode: CassetteMode,
+    pub dir: PathBuf,
+    interactions: Vec<Interaction>,
+    played: Vec<bool>,
+    /// Number of the next recorded file, after those already in the directory
+    next_index: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+    pub body: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    /// JSON bodies are stored as-is, any other body as a string
+    pub body: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+impl Cassette {
+    pub fn new(mode: CassetteMode, dir: &Path) -> Result<Cassette> {
+        let interactions = match mode {
+            CassetteMode::Record => {
+                fs::create_dir_all(dir)?;
+                Vec::new()
+            }
+            CassetteMode::Replay => load_interactions(dir)?,
+        };
+        Ok(Cassette {
+            mode,
+            dir: dir.to_path_buf(),
+            played: vec![false; interactions.len()],
+            interactions,
+            next_index: next_index(dir)?,
+        })
+    }
+
+    /// Finds the response recorded for the request.
+    ///
+    /// Each interaction is played once in order; when all the matching ones have been played the
+    /// last one is served again, so that polling loops can be replayed.
+    fn find(&mut self, recorded_req: &RecordedRequest) -> Option<&RecordedResponse> {
+        let matching: Vec<usize> = self
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, interaction)| interaction.request == *recorded_req)
+            .map(|(index, _)| index)
+            .collect();
+        let index = matching
+            .iter()
+            .find(|index| !self.played[**index])
+            .or(matching.last())
+            .copied()?;
+        self.played[index] = true;
+        Some(&self.interactions[index].response)
+    }
+
+    fn save(&mut self, interaction: Interaction) -> Result<()> {
+        let slug: String = interaction
+            .request
+            .path
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let file_name = format!(
+            "{:04}-{}{}.json",
+            self.next_index,
+            interaction.request.method.to_lowercase(),
+            slug.trim_end_matches('-')
+        );
+        let content = serde_json::to_string_pretty(&interaction)?;
+        fs::write(self.dir.join(file_name), content)?;
+        self.next_index += 1;
+        self.interactions.push(interaction);
+        self.played.push(true);
+        Ok(())
+    }
+}
+
+/// Records every request made by the process into `dir`
+pub fn record_to(dir: &Path) -> Result<()> {
+    insert(Some(Cassette::new(CassetteMode::Record, dir)?));
+    Ok(())
+}
+
+/// Serves every request made by the process from the cassette stored in `dir`
+pub fn replay_from(dir: &Path) -> Result<()> {
+    insert(Some(Cassette::new(CassetteMode::Replay, dir)?));
+    Ok(())
+}
+
+/// Stops recording or replaying, going back to the network
+pub fn eject() {
+    insert(None);
+}
+
+fn insert(cassette: Option<Cassette>) {
+    *lock() = cassette;
+}
+
+fn lock() -> std::sync::MutexGuard<'static, Option<Cassette>> {
+    CASSETTE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Returns the recorded response when replaying, `None` when the request should hit the network
+pub fn replay(req: &Request) -> Result<Option<HttpResponse>> {
+    let mut cassette = lock();
+    let Some(cassette) = cassette.as_mut() else {
+        return Ok(None);
+    };
+    if cassette.mode != CassetteMode::Replay {
+        return Ok(None);
+    }
+    let recorded_req = to_recorded_request(req);
+    let recorded_res = cassette.find(&recorded_req).ok_or_else(|| {
+        AppError::CassetteError(format!(
+            "No recorded interaction matches {} {}",
+            recorded_req.method, recorded_req.path
+        ))
+    })?;
+    Ok(Some(to_http_response(recorded_res)?))
+}
+
+/// Saves the interaction when recording
+pub fn record(recorded_req: RecordedRequest, res: &HttpResponse) -> Result<()> {
+    let mut cassette = lock();
+    let Some(cassette) = cassette.as_mut() else {
+        return Ok(());
+    };
+    if cassette.mode != CassetteMode::Record {
+        return Ok(());
+    }
+    cassette.save(Interaction {
+        request: recorded_req,
+        response: to_recorded_response(res),
+    })
+}
+
+/// Captures the parts of the request used for matching, with secrets redacted
+pub fn to_recorded_request(req: &Request) -> RecordedRequest {
+    let content_type = req
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok());
+    RecordedRequest {
+        method: req.method().to_string(),
+        path: req.url().path().to_owned(),
+        query: req.url().query().map(|query| {
+            trace::redact_body(
+                query.as_bytes(),
+                Some("application/x-www-form-urlencoded"),
+                |_| REDACTED.to_owned(),
+            )
+        }),
+        body: req
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|body| trace::redact_body(body, content_type, |_| REDACTED.to_owned())),
+    }
+}
+
+fn to_recorded_response(res: &HttpResponse) -> RecordedResponse {
+    let headers = res
+        .headers
+        .iter()
+        .filter(|(name, _)| **name != CONTENT_LENGTH)
+        .filter_map(|(name, value)| {
+            let value = if trace::is_sensitive_header(name) {
+                REDACTED
+            } else {
+                value.to_str().ok()?
+            };
+            Some((name.to_string(), value.to_owned()))
+        })
+        .collect();
+    let body = if res.body.is_empty() {
+        None
+    } else {
+        match serde_json::from_slice::<Value>(&res.body) {
+            Ok(mut json_body) => {
+                redact_tokens(&mut json_body);
+                Some(json_body)
+            }
+            Err(_) => Some(Value::String(
+                String::from_utf8_lossy(&res.body).into_owned(),
+            )),
+        }
+    };
+    RecordedResponse {
+        status: res.status.as_u16(),
+        headers,
+        body,
+    }
+}
+
+/// Replaces the secrets of a response body. Tokens lose their signature but keep their claims,
+/// so that replayed logins still have readable expiry, username and roles.
+fn redact_tokens(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                match field {
+                    Value::Null => (),
+                    Value::String(token) if trace::is_sensitive(key) => {
+                        *token = strip_signature(token).unwrap_or_else(|| REDACTED.to_owned());
+                    }
+                    _ if trace::is_sensitive(key) => *field = Value::String(REDACTED.to_owned()),
+                    _ => redact_tokens(field),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_tokens),
+        _ => (),
+    }
+}
+
+fn to_http_response(recorded_res: &RecordedResponse) -> Result<HttpResponse> {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &recorded_res.headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            headers.insert(name, value);
+        }
+    }
+    let is_json = headers
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .is_some_and(|content_type| content_type.contains("json"));
+    let body = match &recorded_res.body {
+        None => Vec::new(),
+        Some(Value::String(text)) if !is_json => text.clone().into_bytes(),
+        Some(json_body) => serde_json::to_vec(json_body)?,
+    };
+    Ok(HttpResponse {
+        status: StatusCode::from_u16(recorded_res.status)
+            .map_err(|err| AppError::Other(Box::new(err)))?,
+        headers,
+        body,
+    })
+}
+
+/// One past the highest number of the recorded files, so that recording into a directory with
+/// gaps or other files never overwrites an interaction
+fn next_index(dir: &Path) -> Result<usize> {
+    let mut next_index = 0;
+    for entry in fs::read_dir(dir)?.flatten() {
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        if let Some((number, _)) = file_name.split_once('-')
+            && let Ok(index) = number.parse::<usize>()
+        {
+            next_index = next_index.max(index + 1);
+        }
+    }
+    Ok(next_index)
+}
+
+fn load_interactions(dir: &Path) -> Result<Vec<Interaction>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .collect();
+    paths.sort();
+    let mut interactions = Vec::new();
+    for path in paths {
+        let content = fs::read_to_string(&path)?;
+        let interaction = serde_json::from_str::<Interaction>(&content).map_err(|err| {
+            AppError::CassetteError(format!("Invalid interaction `{}`: {err}", path.display()))
+        })?;
+        interactions.push(interaction);
+    }
+    Ok(interactions)
+}
@@ -0,0 +1,297 @@
+use reqwest::{
+    Request, StatusCode, Url,
+    header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue},
+};
+use serde_json::Value;
+use std::{sync::RwLock, time::Duration};
+use tracing::debug;
+
+/// Target of the HTTP traces, shown whatever the log level once `--trace-http` or
+/// `--print-curl` asks for them
+pub const HTTP_LOG_TARGET: &str = "vibing::http";
+
+static TRACE_OPTIONS: RwLock<TraceOptions> = RwLock::new(TraceOptions {
+    trace_http: false,
+    print_curl: false,
+});
+
+/// Fields whose values must never be printed in clear text
+const SENSITIVE_FIELDS: [&str; 8] = [
+    "client_secret",
+    "token",
+    "access_token",
+    "refresh_token",
+    "id_token",
+    "device_code",
+    "password",
+    "authorization",
+];
+
+/// Headers carrying credentials or session identifiers, in requests and responses
+const SENSITIVE_HEADERS: [&str; 5] = [
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+];
+
+const REDACTED: &str = "***";
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TraceOptions {
+    /// Log method, URL, headers, bodies, status and timing of every request
+    pub trace_http: bool,
+    /// Log an equivalent `curl` command for every request
+    pub print_curl: bool,
+}
+
+/// Sets the tracing options for the whole process, until the next call (e.g. the next command
+/// of the shell)
+pub fn init(options: TraceOptions) {
+    *TRACE_OPTIONS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = options;
+}
+
+pub fn options() -> TraceOptions {
+    *TRACE_OPTIONS
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub fn trace_request(req: &Request) {
+    let options = options();
+    if options.print_curl {
+        debug!(target: HTTP_LOG_TARGET, "{}", to_curl(req));
+    }
+    if !options.trace_http {
+        return;
+    }
+    let body = req
+        .body()
+        .and_then(|body| body.as_bytes())
+        .map(|body| redact_body(body, content_type(req.headers()), |_| REDACTED.to_owned()));
+    debug!(
+        target: HTTP_LOG_TARGET,
+        headers = %format_headers(req.headers()),
+        body = body.as_deref(),
+        "> {} {}",
+        req.method(),
+        redact_url(req.url())
+    );
+}
+
+pub fn trace_response(status: StatusCode, headers: &HeaderMap, body: &[u8], elapsed: Duration) {
+    if !options().trace_http {
+        return;
+    }
+    let body = (!body.is_empty())
+        .then(|| redact_body(body, content_type(headers), |_| REDACTED.to_owned()));
+    debug!(
+        target: HTTP_LOG_TARGET,
+        headers = %format_headers(headers),
+        body = body.as_deref(),
+        "< {status} ({} ms)",
+        elapsed.as_millis()
+    );
+}
+
+/// `name: value` pairs separated by commas, secrets redacted
+fn format_headers(headers: &HeaderMap) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| format!("{name}: {}", redact_header(name, value)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Builds a `curl` command equivalent to the given request.
+///
+/// Secrets are replaced with shell variables (e.g. `$VIBING_TOKEN`) so that the
+/// command can be reproduced by exporting them beforehand.
+pub fn to_curl(req: &Request) -> String {
+    let mut command = format!(
+        "curl -X {} {}",
+        req.method(),
+        shell_quote(&redact_url(req.url()))
+    );
+    for (name, value) in req.headers() {
+        let value = if name == AUTHORIZATION {
+            format!("Bearer {}", placeholder("authorization"))
+        } else if is_sensitive_header(name) {
+            placeholder(name.as_str())
+        } else {
+            value.to_str().unwrap_or_default().to_owned()
+        };
+        command.push_str(&format!(" -H {}", shell_quote(&format!("{name}: {value}"))));
+    }
+    if let Some(body) = req.body().and_then(|body| body.as_bytes()) {
+        let body = redact_body(body, content_type(req.headers()), placeholder);
+        command.push_str(&format!(" --data-raw {}", shell_quote(&body)));
+    }
+    command
+}
+
+pub fn is_sensitive(field: &str) -> bool {
+    SENSITIVE_FIELDS.contains(&field.to_lowercase().as_str())
+}
+
+pub fn is_sensitive_header(name: &HeaderName) -> bool {
+    SENSITIVE_HEADERS.contains(&name.as_str())
+}
+
+fn redact_header(name: &HeaderName, value: &HeaderValue) -> String {
+    if is_sensitive_header(name) {
+        REDACTED.to_owned()
+    } else {
+        value.to_str().unwrap_or("<binary>").to_owned()
+    }
+}
+
+/// Replaces the values of sensitive fields in a JSON or url-encoded body
+pub fn redact_body(
+    body: &[u8],
+    content_type: Option<&str>,
+    mask: impl Fn(&str) -> String,
+) -> String {
+    let body_str = String::from_utf8_lossy(body);
+    if content_type.is_some_and(|content_type| content_type.contains("x-www-form-urlencoded")) {
+        return redact_form(&body_str, &mask);
+    }
+    match serde_json::from_slice::<Value>(body) {
+        Ok(mut json_body) => {
+            redact_json(&mut json_body, &mask);
+            json_body.to_string()
+        }
+        Err(_) => body_str.into_owned(),
+    }
+}
+
+pub fn redact_json(value: &mut Value, mask: &impl Fn(&str) -> String) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if is_sensitive(key) && !field.is_null() {
+                    *field = Value::String(mask(key));
+                } else {
+                    redact_json(field, mask);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| redact_json(item, mask)),
+        _ => (),
+    }
+}
+
+fn redact_form(body: &str, mask: &impl Fn(&str) -> String) -> String {
+    body.split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) if is_sensitive(key) => format!("{key}={}", mask(key)),
+            _ => pair.to_owned(),
+        })
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+pub fn redact_url(url: &Url) -> String {
+    let mut url = url.clone();
+    if let Some(query) = url.query() {
+        let redacted_query = redact_form(query, &|_| REDACTED.to_owned());
+        url.set_query(Some(&redacted_query));
+    }
+    url.to_string()
+}
+
+fn content_type(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+}
+
+/// Maps a sensitive field to the marker of the shell variable holding its value
+fn placeholder(field: &str) -> String {
+    let variable = match field.to_lowercase().as_str() {
+        "client_secret" => "VIBING_CLIENT_SECRET",
+        "refresh_token" => "VIBING_REFRESH_TOKEN",
+        "token" | "access_token" | "authorization" => "VIBING_TOKEN",
+        _ => "VIBING_SECRET",
+    };
+    format!("@@{variable}@@")
+}
+
+/// Single-quotes a string for POSIX shells, expanding the placeholders into variables
+fn shell_quote(value: &str) -> String {
+    let mut quoted = format!("'{}'", value.replace('\'', r"'\''"));
+    for variable in [
+        "VIBING_CLIENT_SECRET",
+        "VIBING_REFRESH_TOKEN",
+        "VIBING_TOKEN",
+        "VIBING_SECRET",
+    ] {
+        quoted = quoted.replace(&format!("@@{variable}@@"), &format!("'\"${variable}\"'"));
+    }
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::{Method, header::COOKIE};
+
+    #[test]
+    fn redacts_sensitive_query_parameters() {
+        let url = Url::parse("https://vibes.test/token?client_id=vibing&Token=abc&page=1").unwrap();
+        assert_eq!(
+            redact_url(&url),
+            "https://vibes.test/token?client_id=vibing&Token=***&page=1"
+        );
+    }
+
+    #[test]
+    fn redacts_sensitive_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer abc"));
+        headers.insert(COOKIE, HeaderValue::from_static("session=abc"));
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        assert_eq!(
+            format_headers(&headers),
+            "authorization: ***, cookie: ***, content-type: application/json"
+        );
+    }
+
+    #[test]
+    fn redacts_json_and_form_bodies() {
+        let json_body =
+            br#"{"clubName":"Pacha","tokens":[{"access_token":"abc","id_token":null}]}"#;
+        assert_eq!(
+            redact_body(json_body, Some("application/json"), |_| REDACTED.to_owned()),
+            r#"{"clubName":"Pacha","tokens":[{"access_token":"***","id_token":null}]}"#
+        );
+        let form_body = b"grant_type=refresh_token&refresh_token=abc&client_secret=def";
+        assert_eq!(
+            redact_body(
+                form_body,
+                Some("application/x-www-form-urlencoded"),
+                placeholder
+            ),
+            "grant_type=refresh_token&refresh_token=@@VIBING_REFRESH_TOKEN@@&client_secret=@@VIBING_CLIENT_SECRET@@"
+        );
+    }
+
+    #[test]
+    fn replaces_secrets_with_variables_in_curl_commands() {
+        let client = reqwest::Client::new();
+        let req = client
+            .request(Method::POST, "https://vibes.test/api/v1/club?token=abc")
+            .header(AUTHORIZATION, "Bearer abc")
+            .header(CONTENT_TYPE, "application/json")
+            .body(r#"{"password":"it's"}"#)
+            .build()
+            .unwrap();
+        assert_eq!(
+            to_curl(&req),
+            r#"curl -X POST 'https://vibes.test/api/v1/club?token=***' -H 'authorization: Bearer '"$VIBING_TOKEN"'' -H 'content-type: application/json' --data-raw '{"password":"'"$VIBING_SECRET"'"}'"#
+        );
+    }
+}
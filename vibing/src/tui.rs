@@ -0,0 +1,37 @@
+pub mod app;
+pub mod form;
+pub mod ui;
+
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use std::time::Duration;
+
+use crate::{client::VibingClient, error::Result, tui::app::App};
+
+/// How often the screen is redrawn while idle, keeping the opening status live
+const TICK_RATE: Duration = Duration::from_secs(1);
+
+/// Runs the full-screen club browser until the user quits
+pub async fn run(client: VibingClient) -> Result<()> {
+    let mut terminal = ratatui::init();
+    let result = run_app(&mut terminal, &client).await;
+    ratatui::restore();
+    result
+}
+
+async fn run_app(terminal: &mut ratatui::DefaultTerminal, client: &VibingClient) -> Result<()> {
+    let mut app = App::new();
+    app.reload(client).await;
+    while !app.should_quit {
+        terminal.draw(|frame| ui::draw(frame, &mut app))?;
+        if !event::poll(TICK_RATE)? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && let Some(action) = app.handle_key(key)
+        {
+            app.perform(action, client).await;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,329 @@
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    widgets::TableState,
+};
+
+use crate::{
+    authentication::roles::{self, CLUB_ADMIN_ROLE},
+    client::VibingClient,
+    error::AppError,
+    features::club::{cache, request::ClubRequest, response::ClubResponse},
+    requests::{PageDirection, PageOptions},
+    tui::form::EditForm,
+};
+
+/// Number of clubs fetched per page
+pub const PAGE_SIZE: u32 = 20;
+/// Fields the table can be sorted by, cycled through with `s`
+pub const SORT_FIELDS: [&str; 5] = ["id", "clubName", "category", "openingTime", "closingTime"];
+
+#[derive(Debug, Clone)]
+pub enum Mode {
+    Browse,
+    /// Typing the filter
+    Filter,
+    Edit(Box<EditForm>),
+    /// Waiting for the deletion of the club to be confirmed
+    ConfirmDelete(u32),
+}
+
+/// Requests to the backend triggered by the keys
+#[derive(Debug, Clone)]
+pub enum Action {
+    Reload,
+    Save(u32, ClubRequest),
+    Delete(u32),
+    Restore(u32),
+}
+
+#[derive(Debug, Clone)]
+pub struct Status {
+    pub text: String,
+    pub is_error: bool,
+}
+
+pub struct App {
+    /// Clubs of the current page
+    pub clubs: Vec<ClubResponse>,
+    pub page: u32,
+    pub total_count: Option<u64>,
+    /// Index in [`SORT_FIELDS`]
+    pub sort_field: usize,
+    pub direction: PageDirection,
+    /// Case-insensitive text the name, category or address of the listed clubs must contain
+    pub filter: String,
+    pub mode: Mode,
+    pub table_state: TableState,
+    pub status: Option<Status>,
+    pub should_quit: bool,
+}
+
+impl App {
+    pub fn new() -> App {
+        App {
+            clubs: Vec::new(),
+            page: 0,
+            total_count: None,
+            sort_field: 0,
+            direction: PageDirection::Asc,
+            filter: String::new(),
+            mode: Mode::Browse,
+            table_state: TableState::default().with_selected(0),
+            status: None,
+            should_quit: false,
+        }
+    }
+
+    /// The clubs of the loaded page matching the filter, the list API having no search
+    pub fn visible_clubs(&self) -> Vec<&ClubResponse> {
+        let filter = self.filter.to_lowercase();
+        self.clubs
+            .iter()
+            .filter(|club| {
+                filter.is_empty()
+                    || [&club.club_name, &club.category, &club.address]
+                        .iter()
+                        .any(|field| field.to_lowercase().contains(filter.as_str()))
+            })
+            .collect()
+    }
+
+    pub fn selected_club(&self) -> Option<&ClubResponse> {
+        self.visible_clubs()
+            .get(self.table_state.selected()?)
+            .copied()
+    }
+
+    pub fn page_count(&self) -> Option<u64> {
+        self.total_count
+            .map(|total_count| total_count.div_ceil(u64::from(PAGE_SIZE)).max(1))
+    }
+
+    fn has_next_page(&self) -> bool {
+        match self.page_count() {
+            Some(page_count) => u64::from(self.page) + 1 < page_count,
+            None => self.clubs.len() == PAGE_SIZE as usize,
+        }
+    }
+
+    fn set_status(&mut self, text: impl Into<String>) {
+        self.status = Some(Status {
+            text: text.into(),
+            is_error: false,
+        });
+    }
+
+    fn set_error(&mut self, text: impl Into<String>) {
+        self.status = Some(Status {
+            text: text.into(),
+            is_error: true,
+        });
+    }
+
+    fn set_app_error(&mut self, err: &AppError) {
+        self.set_error(err.message().replace('\n', " "));
+    }
+
+    /// Keeps the selection within the visible clubs
+    fn clamp_selection(&mut self) {
+        let visible_count = self.visible_clubs().len();
+        let selected = self.table_state.selected().unwrap_or(0);
+        self.table_state
+            .select(Some(selected.min(visible_count.saturating_sub(1))));
+    }
+
+    /// Updates the state for the key, returning the request it triggers if any
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.should_quit = true;
+            return None;
+        }
+        match self.mode.clone() {
+            Mode::Browse => self.handle_browse_key(key),
+            Mode::Filter => {
+                match key.code {
+                    KeyCode::Enter => self.mode = Mode::Browse,
+                    KeyCode::Esc => {
+                        self.filter.clear();
+                        self.mode = Mode::Browse;
+                    }
+                    KeyCode::Backspace => {
+                        self.filter.pop();
+                    }
+                    KeyCode::Char(character) => self.filter.push(character),
+                    _ => (),
+                }
+                self.clamp_selection();
+                None
+            }
+            Mode::Edit(mut form) => {
+                match key.code {
+                    KeyCode::Esc => {
+                        self.mode = Mode::Browse;
+                        self.set_status("Edit cancelled");
+                        return None;
+                    }
+                    KeyCode::Enter => match form.submit() {
+                        Ok(action) => {
+                            self.mode = Mode::Browse;
+                            return Some(action);
+                        }
+                        Err(reason) => self.set_error(reason),
+                    },
+                    KeyCode::Tab | KeyCode::Down => form.focus_next(),
+                    KeyCode::BackTab | KeyCode::Up => form.focus_previous(),
+                    KeyCode::Backspace => form.pop(),
+                    KeyCode::Char(character) => form.push(character),
+                    _ => (),
+                }
+                self.mode = Mode::Edit(form);
+                None
+            }
+            Mode::ConfirmDelete(club_id) => {
+                self.mode = Mode::Browse;
+                if matches!(key.code, KeyCode::Char('y' | 'Y')) {
+                    return Some(Action::Delete(club_id));
+                }
+                self.set_status("Deletion cancelled");
+                None
+            }
+        }
+    }
+
+    fn handle_browse_key(&mut self, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.table_state.select_next();
+                self.clamp_selection();
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.table_state.select_previous(),
+            KeyCode::Right | KeyCode::Char('n') if self.has_next_page() => {
+                self.page += 1;
+                return Some(Action::Reload);
+            }
+            KeyCode::Left | KeyCode::Char('p') if self.page > 0 => {
+                self.page -= 1;
+                return Some(Action::Reload);
+            }
+            KeyCode::Char('s') => {
+                self.sort_field = (self.sort_field + 1) % SORT_FIELDS.len();
+                self.page = 0;
+                return Some(Action::Reload);
+            }
+            KeyCode::Char('d') => {
+                self.direction = match self.direction {
+                    PageDirection::Asc => PageDirection::Desc,
+                    PageDirection::Desc => PageDirection::Asc,
+                };
+                self.page = 0;
+                return Some(Action::Reload);
+            }
+            KeyCode::Char('/') => self.mode = Mode::Filter,
+            KeyCode::Char('r') => return Some(Action::Reload),
+            KeyCode::Char('e') => {
+                let club = self.selected_club()?;
+                let (is_deleted, form) = (club.deleted, EditForm::new(club));
+                if is_deleted {
+                    self.set_error("Deleted clubs cannot be edited, press u to restore it first");
+                } else if let Err(err) = roles::require_role(CLUB_ADMIN_ROLE) {
+                    self.set_app_error(&err);
+                } else {
+                    self.mode = Mode::Edit(Box::new(form));
+                }
+            }
+            KeyCode::Char('x') => {
+                let club = self.selected_club()?;
+                let (club_id, is_deleted) = (club.id, club.deleted);
+                if is_deleted {
+                    self.set_error("The club is already deleted, press u to restore it");
+                } else if let Err(err) = roles::require_role(CLUB_ADMIN_ROLE) {
+                    self.set_app_error(&err);
+                } else {
+                    self.mode = Mode::ConfirmDelete(club_id);
+                }
+            }
+            KeyCode::Char('u') => {
+                let club = self.selected_club()?;
+                let (club_id, is_deleted) = (club.id, club.deleted);
+                if !is_deleted {
+                    self.set_error("The club is not deleted");
+                } else if let Err(err) = roles::require_role(CLUB_ADMIN_ROLE) {
+                    self.set_app_error(&err);
+                } else {
+                    return Some(Action::Restore(club_id));
+                }
+            }
+            _ => (),
+        }
+        None
+    }
+
+    /// Sends the request, reporting the outcome in the status bar
+    pub async fn perform(&mut self, action: Action, client: &VibingClient) {
+        match action {
+            Action::Reload => self.reload(client).await,
+            Action::Save(club_id, club) => match client.update_club(club_id, &club).await {
+                Ok(club) => {
+                    self.set_status(format!("Saved `{}`", club.club_name));
+                    self.replace_club(club);
+                }
+                Err(err) => self.set_app_error(&err),
+            },
+            Action::Delete(club_id) => match client.delete_club(club_id).await {
+                Ok(()) => {
+                    // Deleted clubs are no longer listed, keep it until the next reload so that
+                    // it can be restored
+                    if let Some(club) = self.clubs.iter_mut().find(|club| club.id == club_id) {
+                        club.deleted = true;
+                        cache::remember(std::slice::from_ref(club));
+                    }
+                    self.set_status(format!("Deleted club {club_id}, press u to restore it"));
+                }
+                Err(err) => self.set_app_error(&err),
+            },
+            Action::Restore(club_id) => match client.restore_club(club_id).await {
+                Ok(club) => {
+                    self.set_status(format!("Restored `{}`", club.club_name));
+                    self.replace_club(club);
+                }
+                Err(err) => self.set_app_error(&err),
+            },
+        }
+    }
+
+    /// Fetches the current page with the current sorting
+    pub async fn reload(&mut self, client: &VibingClient) {
+        let page_options = PageOptions::new(
+            Some(self.page),
+            Some(PAGE_SIZE),
+            Some(SORT_FIELDS[self.sort_field].to_owned()),
+            Some(self.direction),
+            Some(true),
+        );
+        match client.list_clubs(&page_options).await {
+            Ok(page) => {
+                cache::remember(&page.content);
+                self.clubs = page.content;
+                self.total_count = page.total_count;
+                self.status = None;
+            }
+            Err(err) => self.set_app_error(&err),
+        }
+        self.clamp_selection();
+    }
+
+    fn replace_club(&mut self, club: ClubResponse) {
+        cache::remember(std::slice::from_ref(&club));
+        if let Some(listed_club) = self.clubs.iter_mut().find(|listed| listed.id == club.id) {
+            *listed_club = club;
+        }
+        self.clamp_selection();
+    }
+}
+
+impl Default for App {
+    fn default() -> Self {
+        App::new()
+    }
+}
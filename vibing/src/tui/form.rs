@@ -0,0 +1,94 @@
+use crate::{
+    features::club::{opening::parse_time, request::ClubRequest, response::ClubResponse},
+    tui::app::Action,
+};
+
+/// Labels of the editable fields, in the order of [`ClubRequest`]
+pub const FIELD_LABELS: [&str; 8] = [
+    "Name",
+    "VAT code",
+    "Address",
+    "Category",
+    "Opening time",
+    "Closing time",
+    "Phone number",
+    "Image",
+];
+
+/// Edits a club through its [`ClubRequest`] fields
+#[derive(Debug, Clone)]
+pub struct EditForm {
+    pub club_id: u32,
+    pub values: [String; 8],
+    pub focused: usize,
+}
+
+impl EditForm {
+    pub fn new(club: &ClubResponse) -> EditForm {
+        EditForm {
+            club_id: club.id,
+            values: [
+                club.club_name.clone(),
+                club.vat_code.clone(),
+                club.address.clone(),
+                club.category.clone(),
+                club.opening_time.clone(),
+                club.closing_time.clone(),
+                club.phone_number.clone(),
+                club.image.clone().unwrap_or_default(),
+            ],
+            focused: 0,
+        }
+    }
+
+    pub fn focus_next(&mut self) {
+        self.focused = (self.focused + 1) % self.values.len();
+    }
+
+    pub fn focus_previous(&mut self) {
+        self.focused = (self.focused + self.values.len() - 1) % self.values.len();
+    }
+
+    pub fn push(&mut self, character: char) {
+        self.values[self.focused].push(character);
+    }
+
+    pub fn pop(&mut self) {
+        self.values[self.focused].pop();
+    }
+
+    /// The update to send, or what is wrong with the values
+    pub fn submit(&self) -> Result<Action, String> {
+        let [
+            club_name,
+            vat_code,
+            address,
+            category,
+            opening_time,
+            closing_time,
+            phone_number,
+            image,
+        ] = self.values.clone().map(|value| value.trim().to_owned());
+        if club_name.is_empty() {
+            return Err("The name cannot be empty".to_owned());
+        }
+        for time in [&opening_time, &closing_time] {
+            if parse_time(time).is_none() {
+                return Err(format!("`{time}` is not a valid time, use HH:MM:SS"));
+            }
+        }
+        Ok(Action::Save(
+            self.club_id,
+            ClubRequest {
+                club_name,
+                vat_code,
+                address,
+                category,
+                opening_time,
+                closing_time,
+                phone_number,
+                image: (!image.is_empty()).then_some(image),
+            },
+        ))
+    }
+}
@@ -0,0 +1,228 @@
+use chrono::{Local, NaiveTime};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Cell, Clear, Paragraph, Row, Table, Wrap},
+};
+
+use crate::{
+    features::club::{
+        opening::{OpeningStatus, opening_status},
+        response::ClubResponse,
+    },
+    requests::PageDirection,
+    tui::{
+        app::{App, Mode, SORT_FIELDS},
+        form::{EditForm, FIELD_LABELS},
+    },
+};
+
+const HELP: &str = "↑↓ select  ←→ page  s sort  d direction  / filter page  e edit  x delete  u restore  r reload  q quit";
+
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let now = Local::now().time();
+    let [header_area, main_area, status_area, help_area] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [table_area, detail_area] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .areas(main_area);
+
+    frame.render_widget(Paragraph::new(header(app)), header_area);
+    draw_table(frame, app, table_area, now);
+    draw_detail(frame, app, detail_area, now);
+    frame.render_widget(Paragraph::new(status(app)), status_area);
+    frame.render_widget(Paragraph::new(HELP.dark_gray()), help_area);
+
+    match &app.mode {
+        Mode::Edit(form) => draw_form(frame, form),
+        Mode::ConfirmDelete(club_id) => draw_confirmation(frame, *club_id),
+        Mode::Browse | Mode::Filter => (),
+    }
+}
+
+fn header(app: &App) -> Line<'_> {
+    let page = match app.page_count() {
+        Some(page_count) => format!("page {}/{page_count}", app.page + 1),
+        None => format!("page {}", app.page + 1),
+    };
+    let direction = match app.direction {
+        PageDirection::Asc => "↑",
+        PageDirection::Desc => "↓",
+    };
+    let mut spans = vec![
+        " Vibing clubs ".bold().reversed(),
+        format!(
+            "  {page}  sorted by {} {direction}",
+            SORT_FIELDS[app.sort_field]
+        )
+        .into(),
+    ];
+    if let Some(total_count) = app.total_count {
+        spans.push(format!("  {total_count} clubs").into());
+    }
+    if !app.filter.is_empty() || matches!(app.mode, Mode::Filter) {
+        spans.push(format!("  filter (this page): {}", app.filter).yellow());
+        if matches!(app.mode, Mode::Filter) {
+            spans.push("▏".yellow());
+        }
+    }
+    Line::from(spans)
+}
+
+fn status(app: &App) -> Line<'_> {
+    match &app.status {
+        Some(status) if status.is_error => Line::from(status.text.as_str().red()),
+        Some(status) => Line::from(status.text.as_str().green()),
+        None => Line::default(),
+    }
+}
+
+fn draw_table(frame: &mut Frame, app: &mut App, area: Rect, now: NaiveTime) {
+    let rows: Vec<Row> = app
+        .visible_clubs()
+        .into_iter()
+        .map(|club| {
+            let row = Row::new([
+                Cell::from(club.id.to_string()),
+                Cell::from(club.club_name.clone()),
+                Cell::from(club.category.clone()),
+                Cell::from(format!("{}–{}", club.opening_time, club.closing_time)),
+                status_cell(club, now),
+            ]);
+            if club.deleted {
+                row.style(Style::new().dark_gray().add_modifier(Modifier::CROSSED_OUT))
+            } else {
+                row
+            }
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(5),
+            Constraint::Fill(2),
+            Constraint::Fill(1),
+            Constraint::Length(17),
+            Constraint::Length(8),
+        ],
+    )
+    .header(Row::new(["ID", "Name", "Category", "Hours", "Status"]).bold())
+    .row_highlight_style(Style::new().reversed())
+    .block(Block::bordered().title(" Clubs "));
+    frame.render_stateful_widget(table, area, &mut app.table_state);
+}
+
+fn status_cell(club: &ClubResponse, now: NaiveTime) -> Cell<'static> {
+    if club.deleted {
+        return Cell::from("Deleted");
+    }
+    match opening_status(club, now) {
+        Some(OpeningStatus::Open { .. }) => Cell::from("Open".green()),
+        Some(OpeningStatus::Closed { .. }) => Cell::from("Closed".red()),
+        None => Cell::from("?"),
+    }
+}
+
+fn draw_detail(frame: &mut Frame, app: &App, area: Rect, now: NaiveTime) {
+    let block = Block::bordered().title(" Details ");
+    let Some(club) = app.selected_club() else {
+        frame.render_widget(Paragraph::new("No club selected").block(block), area);
+        return;
+    };
+    let opening = if club.deleted {
+        "Deleted".dark_gray()
+    } else {
+        match opening_status(club, now) {
+            Some(status @ OpeningStatus::Open { .. }) => status.to_string().green(),
+            Some(status @ OpeningStatus::Closed { .. }) => status.to_string().red(),
+            None => "Unknown opening hours".into(),
+        }
+    };
+    let fields = [
+        ("ID", club.id.to_string()),
+        ("Name", club.club_name.clone()),
+        ("VAT code", club.vat_code.clone()),
+        ("Address", club.address.clone()),
+        ("Category", club.category.clone()),
+        ("Opening time", club.opening_time.clone()),
+        ("Closing time", club.closing_time.clone()),
+        ("Phone number", club.phone_number.clone()),
+        (
+            "Image",
+            club.image.clone().unwrap_or_else(|| "-".to_owned()),
+        ),
+        ("Deleted", club.deleted.to_string()),
+        ("Created by", club.created_by.clone()),
+        ("Modified by", club.modified_by.clone()),
+    ];
+    let mut lines: Vec<Line> = fields
+        .into_iter()
+        .map(|(label, value)| Line::from(vec![format!("{label}: ").bold(), value.into()]))
+        .collect();
+    lines.push(Line::default());
+    lines.push(Line::from(opening));
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(block),
+        area,
+    );
+}
+
+fn draw_form(frame: &mut Frame, form: &EditForm) {
+    let area = centered(frame.area(), 60, FIELD_LABELS.len() as u16 + 4);
+    let mut lines: Vec<Line> = FIELD_LABELS
+        .iter()
+        .zip(&form.values)
+        .enumerate()
+        .map(|(index, (label, value))| {
+            let label = Span::from(format!("{label:>13}: ")).bold();
+            if index == form.focused {
+                Line::from(vec![label, value.as_str().reversed(), "▏".into()])
+            } else {
+                Line::from(vec![label, value.as_str().into()])
+            }
+        })
+        .collect();
+    lines.push(Line::default());
+    lines.push("Tab/↑↓ move  Enter save  Esc cancel".dark_gray().into());
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::bordered()
+                .title(format!(" Edit club {} ", form.club_id))
+                .border_style(Color::Cyan),
+        ),
+        area,
+    );
+}
+
+fn draw_confirmation(frame: &mut Frame, club_id: u32) {
+    let area = centered(frame.area(), 44, 3);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(format!("Delete club {club_id}? (y/N)")).block(
+            Block::bordered()
+                .title(" Confirm ")
+                .border_style(Color::Red),
+        ),
+        area,
+    );
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
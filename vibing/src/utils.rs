@@ -1,5 +1,16 @@
-pub fn verbose_print(verbose: bool, message: &str) -> () {
-    if verbose {
-        println!("{message}");
+use qrcode::{QrCode, render::unicode::Dense1x2};
+
+/// Renders the data as a QR code made of Unicode half blocks, two modules per character.
+///
+/// Scanners expect dark modules on a light background: `invert` swaps the colors so that the
+/// code reads that way on a dark terminal background.
+pub fn render_qr_code(data: &str, invert: bool) -> Option<String> {
+    let qr_code = QrCode::new(data.as_bytes()).ok()?;
+    let mut renderer = qr_code.render::<Dense1x2>();
+    if invert {
+        renderer
+            .dark_color(Dense1x2::Light)
+            .light_color(Dense1x2::Dark);
     }
+    Some(renderer.quiet_zone(true).build())
 }
@@ -0,0 +1,197 @@
+pub mod condition;
+
+use chrono::Local;
+use ratatui::crossterm::{
+    cursor::MoveTo,
+    execute,
+    style::Stylize,
+    terminal::{Clear, ClearType},
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    io::{self, IsTerminal},
+    time::Duration,
+};
+use tokio::{signal, time};
+use tracing::{info, warn};
+
+use crate::{
+    error::{AppError, Result},
+    output::{self, OutputFormat, format_value, render_template, template::Template},
+    watch::condition::Condition,
+};
+
+pub struct WatchOptions {
+    pub interval: Duration,
+    /// Stops watching once the condition holds
+    pub until: Option<Condition>,
+    /// Shown above the response, e.g. the watched command
+    pub title: String,
+}
+
+/// A printed line, and whether its value changed since the previous poll
+type Line = (String, bool);
+
+/// Fetches and prints the response every interval until interrupted or the condition holds,
+/// highlighting the values which changed since the previous poll.
+///
+/// The output options apply as for a single fetch, JSON output printing one line per result
+/// and poll instead of redrawing. Network failures are shown and retried. Returns `Ok` once the
+/// condition holds and [`AppError::Interrupted`] on Ctrl-C.
+pub async fn watch<T, F>(options: WatchOptions, mut fetch: impl FnMut() -> F) -> Result<()>
+where
+    T: Serialize,
+    F: Future<Output = Result<T>>,
+{
+    let output_options = output::options();
+    let template = output_options
+        .template
+        .as_deref()
+        .map(Template::new)
+        .transpose()?;
+    let is_json = output_options.format == OutputFormat::Json && template.is_none();
+    let is_terminal = io::stdout().is_terminal();
+    let mut previous: Option<Vec<Value>> = None;
+    loop {
+        let fetched = tokio::select! {
+            fetched = fetch() => fetched,
+            _ = signal::ctrl_c() => return Err(AppError::Interrupted),
+        };
+        if !is_json {
+            if is_terminal {
+                execute!(io::stdout(), Clear(ClearType::All), MoveTo(0, 0))?;
+            }
+            println!(
+                "Every {}s: {}    {}\n",
+                options.interval.as_secs(),
+                options.title,
+                Local::now().format("%H:%M:%S")
+            );
+        }
+        match fetched {
+            Ok(response) => {
+                let value = serde_json::to_value(&response)?;
+                let results = output::select(value.clone(), &output_options)?;
+                let mut lines = Vec::new();
+                for (index, result) in results.iter().enumerate() {
+                    if is_json {
+                        lines.push((format_value(result, OutputFormat::Json)?, false));
+                        continue;
+                    }
+                    let previous_result = previous.as_ref().map(|previous| previous.get(index));
+                    match &template {
+                        Some(template) => lines.extend(
+                            render_template(template, result.clone())?
+                                .into_iter()
+                                .map(|line| (line, false)),
+                        ),
+                        None => render_tree(result, previous_result, 0, &mut lines),
+                    }
+                }
+                for (line, is_changed) in lines {
+                    if is_changed && is_terminal {
+                        println!("{}", line.yellow().bold());
+                    } else {
+                        println!("{line}");
+                    }
+                }
+                previous = Some(results);
+                if let Some(condition) = &options.until
+                    && condition.is_met(&value)?
+                {
+                    info!("`{condition}` holds, stopped watching.");
+                    return Ok(());
+                }
+            }
+            Err(err @ (AppError::NetworkError(_) | AppError::FetchError(_))) => {
+                warn!("{}\nRetrying...", err.message());
+            }
+            Err(err) => return Err(err),
+        }
+        tokio::select! {
+            _ = time::sleep(options.interval) => (),
+            _ = signal::ctrl_c() => return Err(AppError::Interrupted),
+        }
+    }
+}
+
+/// Prints the value as indented `key: value` lines.
+///
+/// `previous` is `None` on the first poll, and `Some(None)` when the value did not exist at the
+/// previous one (e.g. a new item of a list).
+fn render_tree(
+    value: &Value,
+    previous: Option<Option<&Value>>,
+    indent: usize,
+    lines: &mut Vec<Line>,
+) {
+    let padding = " ".repeat(indent);
+    match value {
+        Value::Object(object) => {
+            for (key, child) in object {
+                let previous_child =
+                    previous.map(|previous| previous.and_then(|previous| previous.get(key)));
+                render_entry(
+                    format!("{padding}{key}:"),
+                    child,
+                    previous_child,
+                    indent,
+                    lines,
+                );
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                let previous_item = previous
+                    .map(|previous| previous.and_then(|previous| find_item(previous, item, index)));
+                render_entry(format!("{padding}-"), item, previous_item, indent, lines);
+            }
+        }
+        scalar => lines.push((
+            format!("{padding}{}", display(scalar)),
+            is_changed(scalar, previous),
+        )),
+    }
+}
+
+fn render_entry(
+    label: String,
+    value: &Value,
+    previous: Option<Option<&Value>>,
+    indent: usize,
+    lines: &mut Vec<Line>,
+) {
+    match value {
+        Value::Object(_) | Value::Array(_) => {
+            lines.push((label, false));
+            render_tree(value, previous, indent + 2, lines);
+        }
+        scalar => lines.push((
+            format!("{label} {}", display(scalar)),
+            is_changed(scalar, previous),
+        )),
+    }
+}
+
+/// The item of the previous list with the same `id`, or at the same position for items without
+fn find_item<'a>(previous: &'a Value, item: &Value, index: usize) -> Option<&'a Value> {
+    let previous_items = previous.as_array()?;
+    match item.get("id") {
+        Some(id) => previous_items
+            .iter()
+            .find(|previous_item| previous_item.get("id") == Some(id)),
+        None => previous_items.get(index),
+    }
+}
+
+fn is_changed(value: &Value, previous: Option<Option<&Value>>) -> bool {
+    previous.is_some_and(|previous| previous != Some(value))
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        value => value.to_string(),
+    }
+}
@@ -0,0 +1,216 @@
+use serde_json::Value;
+use std::{cmp::Ordering, fmt};
+
+use crate::{
+    error::{AppError, Result},
+    output::{apply_query, is_same_field},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operator {
+    Equal,
+    NotEqual,
+    LessOrEqual,
+    GreaterOrEqual,
+    Less,
+    Greater,
+}
+
+/// Longer operators first, so that `<=` is not read as `<`
+const OPERATORS: [(&str, Operator); 6] = [
+    ("==", Operator::Equal),
+    ("!=", Operator::NotEqual),
+    ("<=", Operator::LessOrEqual),
+    (">=", Operator::GreaterOrEqual),
+    ("<", Operator::Less),
+    (">", Operator::Greater),
+];
+
+/// A comparison such as `deleted == true`, `content.clubName == 'Pacha'` or
+/// `$.totalCount >= 10`.
+///
+/// Fields are dotted paths (going through every item of arrays) or JSONPath expressions, and
+/// the condition holds when any of the selected values matches.
+#[derive(Debug, Clone)]
+pub struct Condition {
+    expression: String,
+    path: String,
+    operator: Operator,
+    expected: Value,
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expression)
+    }
+}
+
+impl Condition {
+    pub fn parse(expression: &str) -> Result<Condition> {
+        let invalid = || AppError::InvalidCondition(expression.to_owned());
+        let (index, symbol, operator) = OPERATORS
+            .iter()
+            .filter_map(|(symbol, operator)| {
+                expression
+                    .find(symbol)
+                    .map(|index| (index, *symbol, *operator))
+            })
+            .min_by_key(|(index, symbol, _)| (*index, usize::MAX - symbol.len()))
+            .ok_or_else(invalid)?;
+        let path = expression[..index].trim();
+        let literal = expression[index + symbol.len()..].trim();
+        if path.is_empty() || literal.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Condition {
+            expression: expression.trim().to_owned(),
+            path: path.to_owned(),
+            operator,
+            expected: parse_literal(literal),
+        })
+    }
+
+    pub fn is_met(&self, value: &Value) -> Result<bool> {
+        let selected = if self.path.starts_with('$') {
+            apply_query(value, self.path.as_str())?
+        } else {
+            select_path(value, self.path.split('.').collect::<Vec<_>>().as_slice())
+        };
+        Ok(selected.iter().any(|actual| self.matches(actual)))
+    }
+
+    fn matches(&self, actual: &Value) -> bool {
+        let ordering = compare(actual, &self.expected);
+        match self.operator {
+            Operator::Equal => ordering == Some(Ordering::Equal),
+            Operator::NotEqual => ordering != Some(Ordering::Equal),
+            Operator::LessOrEqual => {
+                matches!(ordering, Some(Ordering::Less | Ordering::Equal))
+            }
+            Operator::GreaterOrEqual => {
+                matches!(ordering, Some(Ordering::Greater | Ordering::Equal))
+            }
+            Operator::Less => ordering == Some(Ordering::Less),
+            Operator::Greater => ordering == Some(Ordering::Greater),
+        }
+    }
+}
+
+/// JSON literals (`true`, `10`, `null`, `"a"`), quoted strings or bare words
+fn parse_literal(literal: &str) -> Value {
+    if let Ok(value) = serde_json::from_str::<Value>(literal) {
+        return value;
+    }
+    let unquoted = literal
+        .strip_prefix('\'')
+        .and_then(|literal| literal.strip_suffix('\''))
+        .unwrap_or(literal);
+    Value::String(unquoted.to_owned())
+}
+
+fn select_path(value: &Value, segments: &[&str]) -> Vec<Value> {
+    let Some((segment, rest)) = segments.split_first() else {
+        return vec![value.clone()];
+    };
+    match value {
+        Value::Object(object) => object
+            .iter()
+            .filter(|(key, _)| is_same_field(key, segment))
+            .flat_map(|(_, child)| select_path(child, rest))
+            .collect(),
+        Value::Array(items) => match segment.parse::<usize>() {
+            Ok(index) => items
+                .get(index)
+                .map(|item| select_path(item, rest))
+                .unwrap_or_default(),
+            Err(_) => items
+                .iter()
+                .flat_map(|item| select_path(item, segments))
+                .collect(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// Numbers are compared by value and strings alphabetically, other values only for equality
+fn compare(actual: &Value, expected: &Value) -> Option<Ordering> {
+    match (actual, expected) {
+        (Value::Number(actual), Value::Number(expected)) => {
+            actual.as_f64()?.partial_cmp(&expected.as_f64()?)
+        }
+        (Value::String(actual), Value::String(expected)) => Some(actual.cmp(expected)),
+        (actual, expected) if actual == expected => Some(Ordering::Equal),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn page() -> Value {
+        json!({
+            "content": [
+                {"clubName": "Amnesia", "capacity": 5000, "deleted": false},
+                {"clubName": "Pacha", "capacity": 3000, "deleted": true},
+            ],
+            "totalCount": 2,
+        })
+    }
+
+    #[test]
+    fn parses_the_longest_operator() {
+        let condition = Condition::parse(" totalCount <= 2 ").unwrap();
+        assert_eq!(condition.path, "totalCount");
+        assert_eq!(condition.operator, Operator::LessOrEqual);
+        assert_eq!(condition.expected, json!(2));
+        assert_eq!(condition.to_string(), "totalCount <= 2");
+    }
+
+    #[test]
+    fn parses_quoted_and_bare_strings() {
+        let quoted = Condition::parse("content.clubName == 'Pacha'").unwrap();
+        let bare = Condition::parse("content.clubName != Pacha").unwrap();
+        assert_eq!(quoted.expected, json!("Pacha"));
+        assert_eq!(bare.expected, json!("Pacha"));
+        assert_eq!(bare.operator, Operator::NotEqual);
+    }
+
+    #[test]
+    fn rejects_incomplete_conditions() {
+        for expression in ["deleted", "== true", "deleted ==", ""] {
+            assert!(
+                matches!(
+                    Condition::parse(expression),
+                    Err(AppError::InvalidCondition(_))
+                ),
+                "{expression}"
+            );
+        }
+    }
+
+    #[test]
+    fn holds_when_any_selected_value_matches() {
+        let page = page();
+        let is_met =
+            |expression: &str| Condition::parse(expression).unwrap().is_met(&page).unwrap();
+        assert!(is_met("content.club_name == 'Pacha'"));
+        assert!(is_met("content.deleted == true"));
+        assert!(is_met("content.capacity > 4000"));
+        assert!(!is_met("content.capacity < 3000"));
+        assert!(is_met("content.0.clubName == Amnesia"));
+        assert!(!is_met("content.1.clubName == Amnesia"));
+        assert!(is_met("$.totalCount >= 2"));
+        assert!(!is_met("missing == 1"));
+    }
+
+    #[test]
+    fn only_compares_numbers_and_strings_for_order() {
+        let page = page();
+        let condition = Condition::parse("totalCount > 'a'").unwrap();
+        assert!(!condition.is_met(&page).unwrap());
+        let condition = Condition::parse("totalCount != 'a'").unwrap();
+        assert!(condition.is_met(&page).unwrap());
+    }
+}
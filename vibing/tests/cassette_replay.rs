@@ -0,0 +1,24 @@
+use std::path::Path;
+use vibing::{client::VibingClient, error::AppError, requests::cassette};
+
+// The cassette is process-wide, keep the replaying tests of this file in a single test
+#[tokio::test]
+async fn replays_recorded_club_get() {
+    let cassette_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/cassettes/club_get");
+    cassette::replay_from(&cassette_dir).unwrap();
+    // Nothing listens there: every response must come from the cassette
+    let client = VibingClient::builder()
+        .base_url("http://127.0.0.1:9")
+        .token("test-token")
+        .build()
+        .unwrap();
+
+    let club = client.get_club(1).await.unwrap();
+    let unrecorded = client.get_club(2).await;
+    cassette::eject();
+
+    assert_eq!(club.id, 1);
+    assert_eq!(club.club_name, "Amnesia");
+    assert_eq!(club.category, "Disco");
+    assert!(matches!(unrecorded, Err(AppError::CassetteError(_))));
+}